@@ -1,21 +1,61 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Group, TokenStream as TokenStream2, TokenTree};
 use quote::{format_ident, quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, DeriveInput, ItemFn};
+use syn::{
+    parse_macro_input, AttributeArgs, Data, DeriveInput, ItemConst, ItemFn, ItemImpl, ItemStatic,
+    Lit, Meta, MetaNameValue, NestedMeta,
+};
 
 /// Attribute macro that can be used to implement a Dynamite language adapter
+///
+/// By default the adapter supports the versions of Dynamite that are semver compatible with the
+/// version it was built with. A different range can be declared with the inclusive
+/// `min_dynamite_version` and the exclusive `max_dynamite_version`, such as
+/// `#[language_adapter(min_dynamite_version = "0.1.2", max_dynamite_version = "0.3.0")]`.
 #[proc_macro_attribute]
-pub fn language_adapter(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn language_adapter(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
     let input_ = input.clone();
     let derive_input = parse_macro_input!(input_ as DeriveInput);
-    impl_language_adapter(derive_input, input.into()).into()
+    impl_language_adapter(args, derive_input, input.into()).into()
 }
 
-fn impl_language_adapter(derive_input: DeriveInput, raw_input: TokenStream2) -> TokenStream2 {
+fn impl_language_adapter(
+    args: AttributeArgs,
+    derive_input: DeriveInput,
+    raw_input: TokenStream2,
+) -> TokenStream2 {
     let macros_private = quote! { ::dynamite::_macros_private };
     let adapter_ty = derive_input.ident;
 
+    // Get the range of Dynamite versions that the adapter supports
+    let mut min_version = quote! { None };
+    let mut max_version = quote! { None };
+    for arg in &args {
+        match arg {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(version),
+                ..
+            })) if path.is_ident("min_dynamite_version") => {
+                min_version = quote! { Some(#version.into()) };
+            }
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(version),
+                ..
+            })) if path.is_ident("max_dynamite_version") => {
+                max_version = quote! { Some(#version.into()) };
+            }
+            _ => {
+                return quote_spanned! { arg.span() =>
+                    compile_error!{"Expected `min_dynamite_version = \"...\"` or `max_dynamite_version = \"...\"`"}
+                }
+            }
+        }
+    }
+
     let out = quote! {
         // Output the input unchanged
         #raw_input
@@ -28,39 +68,83 @@ fn impl_language_adapter(derive_input: DeriveInput, raw_input: TokenStream2) ->
                 #macros_private::once_cell::sync::OnceCell<dynamite::CHostFunctionPointers>
                 = #macros_private::once_cell::sync::OnceCell::new();
 
-            // Create cell for the adapter
-            static ADAPTER: #macros_private::once_cell::sync::OnceCell<super::#adapter_ty>
+            // Create cell for the adapter instance that the host gave us, which owns our callbacks
+            static ADAPTER_INSTANCE: #macros_private::once_cell::sync::OnceCell<u32>
                 = #macros_private::once_cell::sync::OnceCell::new();
 
+            /// The version of the Dynamite C API that the adapter was built with
+            #[no_mangle]
+            #[allow(non_upper_case_globals)]
+            pub static dynamite_abi_version: u32 = dynamite::ABI_VERSION;
+
+            /// Get the adapter's metadata, which the host checks before loading the adapter
             #[safer_ffi::ffi_export]
-            fn init_adapter(c_host_functions: dynamite::CHostFunctionPointers) {
-                // Initialize host functions cell
-                HOST_FUNCTION_POINTERS.set(c_host_functions).map_err(|_| "Cannot initialize cell twice!").unwrap();
+            fn dynamite_adapter_metadata() -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| dynamite::AdapterMetadata {
+                    name: env!("CARGO_PKG_NAME").into(),
+                    version: env!("CARGO_PKG_VERSION").into(),
+                    dynamite_version: dynamite::VERSION.into(),
+                    min_dynamite_version: #min_version,
+                    max_dynamite_version: #max_version,
+                });
 
-                // Initialize adapter
-                ADAPTER.set(super::#adapter_ty::init_adapter())
-                    .map_err(|_| "Cannot initialize cell twice!").unwrap();
+                dynamite::encode_cbor_result(&result)
             }
 
-            #[safer_ffi::ffi_export]
-            fn get_api(dynamite: *const dynamite::Void) -> safer_ffi::prelude::repr_c::Vec<u8> {
-                let e = "Adapter not initialized";
+            // Create cell for the adapter
+            static ADAPTER: #macros_private::once_cell::sync::OnceCell<super::#adapter_ty>
+                = #macros_private::once_cell::sync::OnceCell::new();
+
+            /// Get the initialized adapter and the host functions for the given dynamite host
+            fn adapter_and_host_functions(
+                dynamite: *const dynamite::Void,
+            ) -> Result<(&'static super::#adapter_ty, dynamite::RemoteHostFunctions), dynamite::CallError> {
+                let e = || dynamite::CallError::AdapterError("Adapter not initialized".into());
                 // Get the adapter
-                let adapter = ADAPTER.get().expect(e);
+                let adapter = ADAPTER.get().ok_or_else(e)?;
 
                 // Get host functions
-                let pointers = HOST_FUNCTION_POINTERS.get().expect(e);
+                let pointers = HOST_FUNCTION_POINTERS.get().ok_or_else(e)?;
                 let host_funcs = dynamite::RemoteHostFunctions {
                     dynamite,
-                    pointers: pointers.clone(),
+                    instance: *ADAPTER_INSTANCE.get().ok_or_else(e)?,
+                    pointers: *pointers,
                 };
 
-                // get the api from the adapter
-                let api = adapter.get_api(&host_funcs);
+                Ok((adapter, host_funcs))
+            }
+
+            #[safer_ffi::ffi_export]
+            fn init_adapter(
+                c_host_functions: dynamite::CHostFunctionPointers,
+                instance: u32,
+            ) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| -> Result<(), dynamite::CallError> {
+                    let e = || dynamite::CallError::AdapterError("Cannot initialize adapter twice!".into());
+                    // Initialize host functions cell
+                    HOST_FUNCTION_POINTERS.set(c_host_functions).map_err(|_| e())?;
+                    ADAPTER_INSTANCE.set(instance).map_err(|_| e())?;
+
+                    // Initialize adapter
+                    ADAPTER.set(super::#adapter_ty::init_adapter()).map_err(|_| e())?;
+
+                    Ok(())
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            fn get_api(dynamite: *const dynamite::Void) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| -> Result<dynamite::ScriptApi, dynamite::CallError> {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    // get the api from the adapter
+                    Ok(adapter.get_api(&host_funcs))
+                });
 
                 // Serialize the API and return the bytes
-                #macros_private::serde_cbor::to_vec(&api)
-                    .expect("Could not serialize language adapter API").into()
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
             }
 
             #[safer_ffi::ffi_export]
@@ -68,24 +152,141 @@ fn impl_language_adapter(derive_input: DeriveInput, raw_input: TokenStream2) ->
                 dynamite: *const dynamite::Void,
                 path: safer_ffi::prelude::str::Ref,
                 args: safer_ffi::prelude::c_slice::Ref<*const dynamite::Void>
-            ) -> *const dynamite::Void {
-                let e = "Adapter not initialized";
-                // Get the adapter
-                let adapter = ADAPTER.get().expect(e);
+            ) -> dynamite::CCallResult {
+                dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
 
-                // Get host functions
-                let pointers = HOST_FUNCTION_POINTERS.get().expect(e);
-                let host_funcs = dynamite::RemoteHostFunctions {
-                    dynamite,
-                    pointers: pointers.clone()
-                };
+                    // Forward the call to the adapter
+                    adapter.call_function(
+                        &host_funcs,
+                        path.as_str(),
+                        args.as_slice(),
+                    )
+                })
+                .and_then(|result| result)
+                .into()
+            }
 
-                // Forward the call to the adapter
-                adapter.call_function(
-                    &host_funcs,
-                    path.as_str(),
-                    args.as_slice(),
-                )
+            #[safer_ffi::ffi_export]
+            fn resolve_function(
+                dynamite: *const dynamite::Void,
+                path: safer_ffi::prelude::str::Ref,
+            ) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.resolve_function(&host_funcs, path.as_str())
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            unsafe fn call_slot(
+                dynamite: *const dynamite::Void,
+                slot: u64,
+                args: safer_ffi::prelude::c_slice::Ref<*const dynamite::Void>
+            ) -> dynamite::CCallResult {
+                dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.call_slot(&host_funcs, slot, args.as_slice())
+                })
+                .and_then(|result| result)
+                .into()
+            }
+
+            #[safer_ffi::ffi_export]
+            unsafe fn free_value(
+                dynamite: *const dynamite::Void,
+                path: safer_ffi::prelude::str::Ref,
+                value: *const dynamite::Void,
+            ) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    // Forward the value to the adapter
+                    adapter.free_value(&host_funcs, path.as_str(), value)
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            unsafe fn get_global(
+                dynamite: *const dynamite::Void,
+                path: safer_ffi::prelude::str::Ref,
+                out: *mut dynamite::Void,
+            ) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.get_global(&host_funcs, path.as_str(), out)
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            unsafe fn set_global(
+                dynamite: *const dynamite::Void,
+                path: safer_ffi::prelude::str::Ref,
+                value: *const dynamite::Void,
+            ) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.set_global(&host_funcs, path.as_str(), value)
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            fn shutdown_adapter(dynamite: *const dynamite::Void) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.shutdown(&host_funcs)
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            fn reload_scripts(dynamite: *const dynamite::Void) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.reload_scripts(&host_funcs)
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            fn serialize_state(dynamite: *const dynamite::Void) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.serialize_state(&host_funcs)
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
+            }
+
+            #[safer_ffi::ffi_export]
+            fn restore_state(
+                dynamite: *const dynamite::Void,
+                state: safer_ffi::prelude::c_slice::Ref<u8>,
+            ) -> safer_ffi::prelude::repr_c::Vec<u8> {
+                let result = dynamite::catch_panic(|| {
+                    let (adapter, host_funcs) = adapter_and_host_functions(dynamite)?;
+
+                    adapter.restore_state(&host_funcs, state.as_slice())
+                });
+
+                dynamite::encode_cbor_result(&result.and_then(|result| result))
             }
         }
     };
@@ -94,6 +295,13 @@ fn impl_language_adapter(derive_input: DeriveInput, raw_input: TokenStream2) ->
 }
 
 /// Attribute macro that can be used to automatically create bindings to
+///
+/// Arguments may be taken by reference or by value. By-value arguments must implement `Clone`,
+/// because the caller keeps ownership of the values that it passes and they are cloned before the
+/// function is called. Owned return values are boxed and must be freed by the caller.
+///
+/// `&str` arguments are passed as `str::Ref`s, and `String` arguments are passed as
+/// `repr_c::String`s. Returned `String`s and `&str`s are both returned as owned `repr_c::String`s.
 #[proc_macro_attribute]
 pub fn stockpile_function(_args: TokenStream, input: TokenStream) -> TokenStream {
     let function = parse_macro_input!(input as ItemFn);
@@ -101,139 +309,920 @@ pub fn stockpile_function(_args: TokenStream, input: TokenStream) -> TokenStream
 }
 
 fn impl_stockpile_function(function: ItemFn) -> TokenStream2 {
-    // Output the function unchanged
-    let mut out = quote! {
+    let function_name = &function.sig.ident;
+    let path = quote! {
+        ::dynamite::TypePath::from(concat!(module_path!(), "::", stringify!(#function_name)))
+    };
+    let bindings = function_bindings(&function.sig, quote! { #function_name }, &path, None);
+    let FunctionBindings {
+        items,
+        proxy_function_name,
+        free_pointer,
+        definition,
+        ..
+    } = bindings;
+
+    quote! {
+        // Output the function unchanged
         #function
+
+        #items
+
+        // Create the stockpile entry for the function
+        ::dynamite::_macros_private::inventory::submit!(
+            ::dynamite::StockpileItem {
+                path: #path,
+                script_type: ::dynamite::ScriptType::Function(#definition),
+                function_pointer: Some(#proxy_function_name),
+                free_pointer: #free_pointer,
+            }
+        );
+    }
+}
+
+/// Attribute macro that can be put on an `impl` block to add its methods to the Dynamite stockpile
+///
+/// The type that the methods are implemented on must also be in the stockpile, for instance by
+/// deriving `HasScriptType`. Each method is added to the type's method definitions and is callable
+/// under the path `[type_path]::[method_name]`, with the `self` pointer as the first argument for
+/// methods taking `&self` or `&mut self`.
+#[proc_macro_attribute]
+pub fn stockpile_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let item_impl = parse_macro_input!(input as ItemImpl);
+    impl_stockpile_impl(item_impl).into()
+}
+
+fn impl_stockpile_impl(item_impl: ItemImpl) -> TokenStream2 {
+    // Make sure this isn't a trait impl
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        return quote_spanned! { path.span() =>
+            compile_error!{"Trait impls are not supported, use an inherent impl block"}
+        };
+    }
+
+    // Make sure the impl isn't generic
+    if !item_impl.generics.params.is_empty() {
+        return quote_spanned! { item_impl.generics.span() =>
+            compile_error!{"Generic impl blocks are not supported"}
+        };
+    }
+
+    let self_ty = &*item_impl.self_ty;
+    let mut methods = Vec::new();
+    for item in &item_impl.items {
+        if let syn::ImplItem::Method(method) = item {
+            let method_name = &method.sig.ident;
+            let path = quote! {
+                <#self_ty as ::dynamite::HasScriptType>::script_path()
+                    .join(stringify!(#method_name))
+            };
+            let bindings = function_bindings(
+                &method.sig,
+                quote! { <#self_ty>::#method_name },
+                &path,
+                Some(self_ty),
+            );
+            let FunctionBindings {
+                items,
+                proxy_function_name,
+                free_pointer,
+                definition,
+                receiver,
+            } = bindings;
+
+            methods.push(quote! {
+                #items
+
+                ::dynamite::_macros_private::inventory::submit!(
+                    ::dynamite::StockpileMethod {
+                        self_path: <#self_ty as ::dynamite::HasScriptType>::script_path(),
+                        method: ::dynamite::MethodDefinition {
+                            name: stringify!(#method_name).to_string(),
+                            receiver: #receiver,
+                            function: #definition,
+                        },
+                        function_pointer: #proxy_function_name,
+                        free_pointer: #free_pointer,
+                    }
+                );
+            });
+        }
+    }
+
+    quote! {
+        // Output the impl block unchanged
+        #item_impl
+
+        // Put the proxy functions in an anonymous block so they don't conflict with other methods
+        const _: () = {
+            #(#methods)*
+        };
+    }
+}
+
+/// Attribute macro that adds a `const` to the Dynamite stockpile as a constant
+///
+/// The constant is accessible to scripts under the path `[module_name]::[const_name]`. Its type
+/// must be a primitive number, `bool`, `char`, or `&str`.
+#[proc_macro_attribute]
+pub fn stockpile_const(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let item_const = parse_macro_input!(input as ItemConst);
+    impl_stockpile_const(item_const).into()
+}
+
+fn impl_stockpile_const(item_const: ItemConst) -> TokenStream2 {
+    let ident = &item_const.ident;
+
+    // String constants are `&str`s, which are described by the type that they refer to
+    let ty = match &*item_const.ty {
+        syn::Type::Reference(reference) => &*reference.elem,
+        ty => ty,
+    };
+    let type_path = quote_spanned! { ty.span() =>
+        <#ty as ::dynamite::HasScriptType>::script_path()
+    };
+
+    quote! {
+        // Output the constant unchanged
+        #item_const
+
+        ::dynamite::_macros_private::inventory::submit!(
+            ::dynamite::StockpileItem {
+                path: ::dynamite::TypePath::from(concat!(module_path!(), "::", stringify!(#ident))),
+                script_type: ::dynamite::ScriptType::Constant {
+                    type_path: #type_path,
+                    value: ::dynamite::LiteralValue::from(#ident),
+                },
+                function_pointer: None,
+                free_pointer: None,
+            }
+        );
+    }
+}
+
+/// Attribute macro that adds a `static` to the Dynamite stockpile as a global variable
+///
+/// The global is accessible to scripts under the path `[module_name]::[static_name]`. Globals of
+/// type `RwLock<T>` or `Mutex<T>` can be set by scripts, and any other global is read-only. The
+/// type of the value must implement `Clone` because scripts get and set copies of it.
+#[proc_macro_attribute]
+pub fn stockpile_global(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let item_static = parse_macro_input!(input as ItemStatic);
+    impl_stockpile_global(item_static).into()
+}
+
+fn impl_stockpile_global(item_static: ItemStatic) -> TokenStream2 {
+    let ident = &item_static.ident;
+
+    if let Some(mutability) = &item_static.mutability {
+        return quote_spanned! { mutability.span() =>
+            compile_error!{"`static mut` globals are not supported, use a `RwLock` or `Mutex`"}
+        };
+    }
+
+    // Get the type of the value and an expression that dereferences to it
+    let ty = &*item_static.ty;
+    let (value_ty, read, write) = if let Some([value_ty]) = generic_args(ty, "RwLock").as_deref() {
+        (
+            *value_ty,
+            quote! { #ident.read().unwrap_or_else(::std::sync::PoisonError::into_inner) },
+            Some(quote! { #ident.write().unwrap_or_else(::std::sync::PoisonError::into_inner) }),
+        )
+    } else if let Some([value_ty]) = generic_args(ty, "Mutex").as_deref() {
+        let lock = quote! { #ident.lock().unwrap_or_else(::std::sync::PoisonError::into_inner) };
+        (*value_ty, lock.clone(), Some(lock))
+    } else {
+        (ty, quote! { &#ident }, None)
+    };
+
+    let set_pointer = match write {
+        Some(write) => quote! {
+            Some({
+                unsafe fn set(value: *const ::dynamite::Void) {
+                    *#write = <#value_ty as ::core::clone::Clone>::clone(&*(value as *const #value_ty));
+                }
+                set
+            })
+        },
+        None => quote! { None },
+    };
+
+    quote! {
+        // Output the static unchanged
+        #item_static
+
+        const _: () = {
+            unsafe fn get(out: *mut ::dynamite::Void) {
+                ::core::ptr::write(
+                    out as *mut #value_ty,
+                    <#value_ty as ::core::clone::Clone>::clone(&*#read),
+                );
+            }
+
+            ::dynamite::_macros_private::inventory::submit!(
+                ::dynamite::StockpileGlobal {
+                    path: ::dynamite::TypePath::from(
+                        concat!(module_path!(), "::", stringify!(#ident))
+                    ),
+                    type_path: <#value_ty as ::dynamite::HasScriptType>::script_path(),
+                    get_pointer: get,
+                    set_pointer: #set_pointer,
+                }
+            );
+        };
+    }
+}
+
+/// Check whether the type is `str`
+fn is_str(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("str"))
+}
+
+/// Check whether the type is `String`
+fn is_string(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("String"))
+}
+
+/// The generated bindings for a function or method
+struct FunctionBindings {
+    /// Items that must be output along with the function, such as the FFI proxy function
+    items: TokenStream2,
+    /// The name of the generated proxy function
+    proxy_function_name: syn::Ident,
+    /// An expression for the `Option` of the function used to free owned return values
+    free_pointer: TokenStream2,
+    /// An expression for the function's `FunctionDefinition`
+    definition: TokenStream2,
+    /// An expression for the function's `Receiver`
+    receiver: TokenStream2,
+}
+
+/// Generate the bindings for a function with the given signature
+///
+/// `callee` is the expression used to call the function and `path` is an expression for the path
+/// it is stockpiled under. `self_ty` must be set for methods, and is used to allow `self` arguments
+/// and to replace `Self` in argument and return types.
+fn function_bindings(
+    sig: &syn::Signature,
+    callee: TokenStream2,
+    path: &TokenStream2,
+    self_ty: Option<&syn::Type>,
+) -> FunctionBindings {
+    let mut items = TokenStream2::new();
+
+    // Replace `Self` with the self type, because the generated functions are not in the impl block
+    let resolve_ty = |ty: &syn::Type| match self_ty {
+        Some(self_ty) => replace_self(quote! { #ty }, self_ty),
+        None => quote! { #ty },
     };
 
     // Output a proxy function with the signature required for FFI
-    let function_name = function.sig.ident.clone();
-    let proxy_function_name = format_ident!("{}_dynamite_ptr", function.sig.ident);
+    let proxy_function_name = format_ident!("{}_dynamite_ptr", sig.ident);
+
+    // Strings are passed across the FFI boundary as `safer_ffi` strings
+    let ffi_string = quote! { ::dynamite::_macros_private::safer_ffi::String };
+
+    /// How an argument is passed to the function
+    enum ArgKind {
+        /// By shared reference
+        Shared,
+        /// By mutable reference
+        Mutable,
+        /// By value, which is cloned from the argument pointer so that the caller keeps ownership
+        Value,
+        /// A `&str`, which is passed as a `str::Ref`
+        Str,
+        /// A `String`, which is copied from a `repr_c::String`
+        String,
+    }
 
     struct ArgInfo {
         index: usize,
-        ident: syn::Ident,
-        argtype: syn::Type,
+        name: String,
+        argtype: TokenStream2,
+        kind: ArgKind,
     }
 
     // Validate and collect argument_info
     let mut arg_infos = Vec::new();
-    for (i, arg) in function.sig.inputs.iter().enumerate() {
-        // Make sure arg is not `self`
-        if let syn::FnArg::Typed(arg) = arg {
-            // Make sure arg is a referenece
-            if let syn::Type::Reference(argtype) = &*arg.ty {
-                let argtype = &argtype.elem;
+    let mut receiver = quote! { ::dynamite::Receiver::Static };
+    for (i, arg) in sig.inputs.iter().enumerate() {
+        match arg {
+            syn::FnArg::Typed(arg) => {
+                // Get the type of the argument and how it is passed
+                let (argtype, kind) = match &*arg.ty {
+                    syn::Type::Reference(argtype)
+                        if argtype.mutability.is_none() && is_str(&argtype.elem) =>
+                    {
+                        (quote! { str }, ArgKind::Str)
+                    }
+                    syn::Type::Reference(argtype) if argtype.mutability.is_some() => {
+                        (resolve_ty(&argtype.elem), ArgKind::Mutable)
+                    }
+                    syn::Type::Reference(argtype) => (resolve_ty(&argtype.elem), ArgKind::Shared),
+                    argtype if is_string(argtype) => (ffi_string.clone(), ArgKind::String),
+                    argtype => (resolve_ty(argtype), ArgKind::Value),
+                };
 
                 // Make sure argument name is a simple identifier
                 if let syn::Pat::Ident(ident) = &*arg.pat {
                     arg_infos.push(ArgInfo {
                         index: i,
-                        ident: ident.ident.clone(),
-                        argtype: (**argtype).clone(),
+                        name: ident.ident.to_string(),
+                        argtype,
+                        kind,
                     });
                 } else {
-                    let e = quote_spanned! { arg.pat.span() =>
+                    items.extend(quote_spanned! { arg.pat.span() =>
                         compile_error!{"Arg name must be an identifier,"}
-                    };
-                    out = quote! { #out #e };
+                    });
                 }
-            } else {
-                let e = quote_spanned! { arg.ty.span() =>
-                    compile_error!{"All args must be a reference"}
-                };
-                out = quote! { #out #e };
             }
-        } else {
-            let e = quote_spanned! { arg.span() =>
-                compile_error!{"Methods with `self` args are no supported"}
-            };
-            out = quote! { #out #e };
+            syn::FnArg::Receiver(arg) => match self_ty {
+                Some(self_ty) => {
+                    let (kind, receiver_tokens) = match (&arg.reference, &arg.mutability) {
+                        (Some(_), Some(_)) => {
+                            (ArgKind::Mutable, quote! { ::dynamite::Receiver::Mutable })
+                        }
+                        (Some(_), None) => (ArgKind::Shared, quote! { ::dynamite::Receiver::Shared }),
+                        (None, _) => (ArgKind::Value, quote! { ::dynamite::Receiver::Value }),
+                    };
+                    receiver = receiver_tokens;
+                    arg_infos.push(ArgInfo {
+                        index: i,
+                        name: "self".into(),
+                        argtype: quote! { #self_ty },
+                        kind,
+                    });
+                }
+                None => items.extend(quote_spanned! { arg.span() =>
+                    compile_error!{"Methods with `self` args must be in a `#[stockpile_impl]` block"}
+                }),
+            },
         }
     }
 
     // Get the function return type
-    let return_type = match function.sig.output {
-        syn::ReturnType::Default => None,
+    enum ReturnKind {
+        /// The function doesn't return anything
+        Nothing,
+        /// The function returns a reference
+        Reference { ty: TokenStream2, is_static: bool },
+        /// The function returns an owned value
+        Value(TokenStream2),
+        /// The function returns a `String`, or a `&str` that is copied into one, which is returned
+        /// as a `repr_c::String`
+        String { borrowed: bool },
+    }
+    let mut fallible = false;
+    let mut optional_return = false;
+    let return_kind = match &sig.output {
+        syn::ReturnType::Default => ReturnKind::Nothing,
         syn::ReturnType::Type(_, t) => {
-            if let syn::Type::Reference(t) = &*t {
-                let t = &*t.elem;
-                Some(t.clone())
-            } else {
-                return quote_spanned! {t.span() =>
-                    compile_error!{"Return type must be a reference"}
-                };
+            let mut t = &**t;
+
+            // Unwrap `Result` and `Option` return types. One-argument aliases such as
+            // `io::Result<T>` are fallible too, but the error type of aliases without arguments
+            // can't be seen by the macro.
+            match generic_args(t, "Result").as_deref() {
+                Some([ok_type]) | Some([ok_type, _]) => {
+                    fallible = true;
+                    t = ok_type;
+                }
+                Some(_) => items.extend(quote_spanned! { t.span() =>
+                    compile_error!{"Result return types must be written as `Result<T, E>` or `Result<T>`"}
+                }),
+                None => (),
+            }
+            if let Some([some_type]) = generic_args(t, "Option").as_deref() {
+                optional_return = true;
+                t = some_type;
+            }
+
+            match t {
+                syn::Type::Tuple(tuple) if tuple.elems.is_empty() => ReturnKind::Nothing,
+                syn::Type::Reference(t) if is_str(&t.elem) => ReturnKind::String { borrowed: true },
+                t if is_string(t) => ReturnKind::String { borrowed: false },
+                syn::Type::Reference(t) => ReturnKind::Reference {
+                    ty: resolve_ty(&t.elem),
+                    is_static: t
+                        .lifetime
+                        .as_ref()
+                        .map(|l| l.ident == "static")
+                        .unwrap_or(false),
+                },
+                t => ReturnKind::Value(resolve_ty(t)),
             }
         }
     };
 
     // Create our FFI compatible proxy function
-    let return_tokens = if let Some(return_type) = &return_type {
-        quote! {
-            as *const #return_type as *const ::dynamite::Void
-        }
-    } else {
-        quote! {
-            ; std::ptr::null()
-        }
-    };
     let cast_function_args = arg_infos
         .iter()
         .map(|x| {
-            let argtype = x.argtype.clone();
+            let argtype = &x.argtype;
             let index = x.index;
-            quote! {
-                &*(args[#index] as *const #argtype)
+            match x.kind {
+                ArgKind::Shared => quote! {
+                    &*(args[#index] as *const #argtype)
+                },
+                ArgKind::Mutable => quote! {
+                    &mut *(args[#index] as *mut #argtype)
+                },
+                // Arguments are always borrowed from the caller, so by-value arguments must be
+                // cloned. This is a plain copy for `Copy` types.
+                ArgKind::Value => quote_spanned! { argtype.span() =>
+                    <#argtype as ::core::clone::Clone>::clone(&*(args[#index] as *const #argtype))
+                },
+                ArgKind::Str => quote! {
+                    (*(args[#index]
+                        as *const ::dynamite::_macros_private::safer_ffi::prelude::str::Ref<'_>))
+                        .as_str()
+                },
+                ArgKind::String => quote! {
+                    ::std::string::String::from(&**(args[#index] as *const #argtype))
+                },
             }
         })
         .collect::<Vec<_>>();
-    out = quote! {
-        #out
+    let mut call = quote! { #callee(#( #cast_function_args ),*) };
+
+    // Errors are returned to the caller as script errors
+    if fallible {
+        call = quote! {
+            match #call {
+                Ok(value) => value,
+                Err(error) => return Err(::dynamite::ScriptError::new(&error).into()),
+            }
+        };
+    }
 
-        unsafe fn #proxy_function_name (args: &[*const ::dynamite::Void]) -> *const dynamite::Void {
-           #function_name(#( #cast_function_args ),*) #return_tokens
+    // Convert the returned value to a pointer
+    let to_pointer = |value: TokenStream2| match &return_kind {
+        ReturnKind::Nothing => quote! {
+            {
+                #value;
+                std::ptr::null()
+            }
+        },
+        ReturnKind::Reference { ty, .. } => quote! {
+            #value as *const #ty as *const ::dynamite::Void
+        },
+        // Owned values are boxed and must be freed with the free function below
+        ReturnKind::Value(ty) => quote! {
+            Box::into_raw(Box::<#ty>::new(#value)) as *const ::dynamite::Void
+        },
+        ReturnKind::String { borrowed } => {
+            let string = if *borrowed {
+                quote! { ::std::string::String::from(#value) }
+            } else {
+                value
+            };
+            quote! {
+                Box::into_raw(Box::new(#ffi_string::from(#string))) as *const ::dynamite::Void
+            }
         }
     };
-
-    // Create the stockpile entry for the method
-    let api_return_tokens = if let Some(return_type) = &return_type {
+    let proxy_body = if optional_return {
+        // `None` is returned as a null pointer
+        let some_pointer = to_pointer(quote! { value });
         quote! {
-            Some(<#return_type as ::dynamite::HasScriptType>::script_path())
+            match #call {
+                Some(value) => #some_pointer,
+                None => std::ptr::null(),
+            }
+        }
+    } else {
+        to_pointer(call)
+    };
+    // The arguments are indexed by the proxy, so it must check how many it was given
+    let arg_count = arg_infos.len();
+    items.extend(quote! {
+        unsafe fn #proxy_function_name (
+            args: &[*const ::dynamite::Void]
+        ) -> Result<*const ::dynamite::Void, ::dynamite::CallError> {
+            if args.len() != #arg_count {
+                return Err(::dynamite::CallError::WrongArity {
+                    path: #path,
+                    expected: #arg_count,
+                    found: args.len(),
+                });
+            }
+
+            Ok(#proxy_body)
         }
+    });
+
+    // Create the function used to free owned return values
+    let owned_type = match &return_kind {
+        ReturnKind::Value(ty) => Some(ty),
+        ReturnKind::String { .. } => Some(&ffi_string),
+        _ => None,
+    };
+    let free_pointer = if let Some(ty) = owned_type {
+        let free_function_name = format_ident!("{}_dynamite_free", sig.ident);
+        items.extend(quote! {
+            unsafe fn #free_function_name (value: *const ::dynamite::Void) {
+                // Optional functions return `None` as a null pointer, which has nothing to free
+                if value.is_null() {
+                    return;
+                }
+
+                drop(Box::from_raw(value as *mut #ty));
+            }
+        });
+
+        quote! { Some(#free_function_name) }
     } else {
         quote! { None }
     };
-    let function_arg_script_paths = arg_infos
+
+    // Create the definition of the function
+    let api_return_tokens = match &return_kind {
+        ReturnKind::Nothing => quote! { None },
+        ReturnKind::Reference { ty, .. } | ReturnKind::Value(ty) => quote! {
+            Some(<#ty as ::dynamite::HasScriptType>::script_path())
+        },
+        ReturnKind::String { .. } => quote! {
+            Some(<#ffi_string as ::dynamite::HasScriptType>::script_path())
+        },
+    };
+    let return_ownership = match &return_kind {
+        ReturnKind::Nothing => quote! { ::dynamite::ReturnOwnership::Borrowed },
+        ReturnKind::Reference { is_static, .. } => {
+            if *is_static {
+                quote! { ::dynamite::ReturnOwnership::OwnedByCallee }
+            } else {
+                quote! { ::dynamite::ReturnOwnership::Borrowed }
+            }
+        }
+        ReturnKind::Value(_) | ReturnKind::String { .. } => {
+            quote! { ::dynamite::ReturnOwnership::OwnedByCaller }
+        }
+    };
+    let function_arguments = arg_infos
         .iter()
         .map(|x| {
-            let argtype = x.argtype.clone();
-            let ident = x.ident.clone();
+            let argtype = &x.argtype;
+            let name = &x.name;
             quote_spanned! {argtype.span() =>
-                h.insert(stringify!(#ident).into(), <#argtype as ::dynamite::HasScriptType>::script_path());
+                ::dynamite::FunctionArgument::new(
+                    #name,
+                    <#argtype as ::dynamite::HasScriptType>::script_path(),
+                )
             }
         })
         .collect::<Vec<_>>();
-    out = quote! {
-        #out
-        ::dynamite::_macros_private::inventory::submit!(
-            ::dynamite::StockpileItem {
-                path: ::dynamite::TypePath::from(
-                    concat!(module_path!(), "::", stringify!(#function_name))
-                ),
-                script_type: ::dynamite::ScriptType::Function(::dynamite::FunctionDefinition {
-                    arguments: {
-                        let mut h = std::collections::HashMap::new();
+    let definition = quote! {
+        ::dynamite::FunctionDefinition {
+            arguments: vec![#(#function_arguments),*],
+            return_type: #api_return_tokens,
+            return_ownership: #return_ownership,
+            fallible: #fallible,
+            optional_return: #optional_return,
+        }
+    };
 
-                        #(#function_arg_script_paths)*
+    FunctionBindings {
+        items,
+        proxy_function_name,
+        free_pointer,
+        definition,
+        receiver,
+    }
+}
+
+/// Get the generic type arguments of a type with the given name, such as the `T` and `E` of
+/// `Result<T, E>`
+///
+/// Only the last segment of the type's path is checked, so this also matches `std::result::Result`.
+/// Types with the given name but without generic arguments return an empty list.
+fn generic_args<'a>(ty: &'a syn::Type, name: &str) -> Option<Vec<&'a syn::Type>> {
+    let path = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => Some(
+            args.args
+                .iter()
+                .filter_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        syn::PathArguments::None => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+/// Replace every `Self` in the tokens with the given type
+fn replace_self(tokens: TokenStream2, self_ty: &syn::Type) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => quote! { #self_ty },
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    Group::new(group.delimiter(), replace_self(group.stream(), self_ty));
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group).into()
+            }
+            token => token.into(),
+        })
+        .collect()
+}
+
+/// Derive macro that implements `HasScriptType` for a `#[repr(C)]` struct or enum and adds the type
+/// to the Dynamite stockpile
+///
+/// The type is accessible to scripts under the path `[module_name]::[type_name]`, and all of its
+/// fields must implement `HasScriptType` themselves. Enums may also use a primitive integer
+/// representation such as `#[repr(u8)]` instead of `#[repr(C)]`. Packed structs are not supported
+/// because their fields may not be aligned.
+#[proc_macro_derive(HasScriptType)]
+pub fn derive_has_script_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    impl_derive_has_script_type(input).into()
+}
 
-                        h
+fn impl_derive_has_script_type(input: DeriveInput) -> TokenStream2 {
+    let ident = &input.ident;
+
+    // Make sure the type isn't generic
+    if !input.generics.params.is_empty() {
+        return quote_spanned! { input.generics.span() =>
+            compile_error!{"HasScriptType cannot be derived for generic types"}
+        };
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => return impl_derive_has_script_type_enum(&input, data),
+        Data::Union(_) => {
+            return quote_spanned! { ident.span() =>
+                compile_error!{"HasScriptType can only be derived for structs and enums"}
+            }
+        }
+    };
+
+    // Make sure the type has a defined layout
+    if !has_repr(&input.attrs, "C") {
+        return quote_spanned! { ident.span() =>
+            compile_error!{"HasScriptType can only be derived for `#[repr(C)]` structs"}
+        };
+    }
+
+    // Packed structs may have unaligned fields, which can't be referenced by scripts
+    if let Some(packed) = packed_repr(&input.attrs) {
+        return quote_spanned! { packed.span() =>
+            compile_error!{"HasScriptType cannot be derived for `#[repr(packed)]` structs"}
+        };
+    }
+
+    // Collect the name, type path, offset, and layout of every field
+    let struct_fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let ty = &field.ty;
+            let member = match &field.ident {
+                Some(ident) => quote! { #ident },
+                None => {
+                    let index = syn::Index::from(i);
+                    quote! { #index }
+                }
+            };
+            quote_spanned! { ty.span() =>
+                ::dynamite::StructField {
+                    name: stringify!(#member).to_string(),
+                    type_path: <#ty as ::dynamite::HasScriptType>::script_path(),
+                    offset: ::core::mem::offset_of!(#ident, #member),
+                    layout: ::dynamite::DataLayout::of::<#ty>(),
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl ::dynamite::HasScriptType for #ident {
+            fn script_type() -> ::dynamite::ScriptType {
+                ::dynamite::ScriptType::Struct(::dynamite::StructDefinition {
+                    layout: ::dynamite::DataLayout::of::<#ident>(),
+                    component_type: ::dynamite::DataType::Struct {
+                        fields: vec![#(#struct_fields),*],
                     },
-                    return_type: #api_return_tokens,
-                }),
-                function_pointer: Some(#proxy_function_name)
+                    method_definitions: vec![],
+                })
+            }
+
+            fn script_path() -> ::dynamite::TypePath {
+                ::dynamite::TypePath::from(concat!(module_path!(), "::", stringify!(#ident)))
+            }
+        }
+
+        ::dynamite::_macros_private::inventory::submit!(
+            ::dynamite::StockpileItem {
+                path: <#ident as ::dynamite::HasScriptType>::script_path(),
+                script_type: <#ident as ::dynamite::HasScriptType>::script_type(),
+                function_pointer: None,
+                free_pointer: None,
             }
         );
+    }
+}
+
+/// Implement `HasScriptType` for an enum with a `#[repr(C)]` or primitive integer representation
+fn impl_derive_has_script_type_enum(input: &DeriveInput, data: &syn::DataEnum) -> TokenStream2 {
+    let ident = &input.ident;
+
+    // Get the representation of the enum's tag
+    let int_repr = INT_REPRS
+        .iter()
+        .find(|repr| has_repr(&input.attrs, repr))
+        .map(|repr| format_ident!("{}", repr));
+    let is_repr_c = has_repr(&input.attrs, "C");
+    let tag_repr = match (&int_repr, is_repr_c) {
+        (Some(int_repr), _) => quote! { #int_repr },
+        (None, true) => quote! { C },
+        (None, false) => {
+            return quote_spanned! { ident.span() =>
+                compile_error!{"HasScriptType can only be derived for enums with a `#[repr(C)]` or integer representation"}
+            }
+        }
+    };
+    let tag_type = match &int_repr {
+        Some(int_repr) => quote! { #int_repr },
+        None => quote! { ::std::os::raw::c_int },
     };
 
-    out
+    if data.variants.is_empty() {
+        return quote_spanned! { ident.span() =>
+            compile_error!{"HasScriptType cannot be derived for enums without variants"}
+        };
+    }
+
+    // To get the discriminants and field offsets we create mirror types with the same layout as
+    // the enum, as described in RFC 2195: a C-like enum for the tag, and a `#[repr(C)]` struct for
+    // the fields of each variant.
+    let tag_variants = data.variants.iter().map(|variant| {
+        let name = &variant.ident;
+        match &variant.discriminant {
+            Some((_, discriminant)) => quote! { #name = #discriminant },
+            None => quote! { #name },
+        }
+    });
+    let mut mirror_types = quote! {
+        #[repr(#tag_repr)]
+        #[allow(dead_code)]
+        enum __DynamiteTag {
+            #(#tag_variants),*
+        }
+    };
+
+    let mut union_fields = Vec::new();
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        let name = &variant.ident;
+        let struct_name = format_ident!("__DynamiteVariant{}", name);
+        let field_types = variant
+            .fields
+            .iter()
+            .map(|field| &field.ty)
+            .collect::<Vec<_>>();
+
+        // With a primitive representation every variant is a struct starting with the tag. With
+        // `#[repr(C)]` the tag is followed by a union of the variant's structs.
+        let (struct_fields, offset_base, first_field) = if is_repr_c {
+            (
+                quote! { #(#field_types),* },
+                quote! { ::core::mem::offset_of!(__DynamiteRepr, variants) },
+                0,
+            )
+        } else {
+            (quote! { __DynamiteTag, #(#field_types),* }, quote! { 0 }, 1)
+        };
+        mirror_types.extend(quote! {
+            #[repr(C)]
+            #[allow(dead_code)]
+            struct #struct_name(#struct_fields);
+        });
+        union_fields.push(quote! {
+            #name: ::core::mem::ManuallyDrop<#struct_name>
+        });
+
+        let fields = variant.fields.iter().enumerate().map(|(i, field)| {
+            let ty = &field.ty;
+            let field_name = match &field.ident {
+                Some(field_ident) => field_ident.to_string(),
+                None => i.to_string(),
+            };
+            let index = syn::Index::from(i + first_field);
+            quote_spanned! { ty.span() =>
+                ::dynamite::StructField {
+                    name: #field_name.to_string(),
+                    type_path: <#ty as ::dynamite::HasScriptType>::script_path(),
+                    offset: #offset_base + ::core::mem::offset_of!(#struct_name, #index),
+                    layout: ::dynamite::DataLayout::of::<#ty>(),
+                }
+            }
+        });
+        variants.push(quote! {
+            ::dynamite::EnumVariant {
+                name: stringify!(#name).to_string(),
+                discriminant: __DynamiteTag::#name as i64,
+                fields: vec![#(#fields),*],
+            }
+        });
+    }
+
+    if is_repr_c {
+        mirror_types.extend(quote! {
+            #[repr(C)]
+            #[allow(dead_code, non_snake_case)]
+            union __DynamiteVariants {
+                #(#union_fields),*
+            }
+
+            #[repr(C)]
+            #[allow(dead_code)]
+            struct __DynamiteRepr {
+                tag: __DynamiteTag,
+                variants: __DynamiteVariants,
+            }
+        });
+    }
+
+    quote! {
+        impl ::dynamite::HasScriptType for #ident {
+            fn script_type() -> ::dynamite::ScriptType {
+                #mirror_types
+
+                ::dynamite::ScriptType::Enum(::dynamite::EnumDefinition {
+                    layout: ::dynamite::DataLayout::of::<#ident>(),
+                    tag_type: <#tag_type as ::dynamite::HasScriptType>::script_path(),
+                    variants: vec![#(#variants),*],
+                })
+            }
+
+            fn script_path() -> ::dynamite::TypePath {
+                ::dynamite::TypePath::from(concat!(module_path!(), "::", stringify!(#ident)))
+            }
+        }
+
+        ::dynamite::_macros_private::inventory::submit!(
+            ::dynamite::StockpileItem {
+                path: <#ident as ::dynamite::HasScriptType>::script_path(),
+                script_type: <#ident as ::dynamite::HasScriptType>::script_type(),
+                function_pointer: None,
+                free_pointer: None,
+            }
+        );
+    }
+}
+
+/// The primitive integer representations that an enum may have
+const INT_REPRS: &[&str] = &[
+    "u8", "u16", "u32", "u64", "usize", "i8", "i16", "i32", "i64", "isize",
+];
+
+/// Check whether the attributes contain a `#[repr(...)]` attribute with the given representation
+fn has_repr(attrs: &[syn::Attribute], repr: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            syn::Meta::List(list) => list.nested.iter().any(|nested| {
+                matches!(nested, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident(repr))
+            }),
+            _ => false,
+        })
+}
+
+/// Get the `packed` or `packed(N)` representation in the attributes, if there is one
+fn packed_repr(attrs: &[syn::Attribute]) -> Option<syn::Path> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::Path(path))
+            | syn::NestedMeta::Meta(syn::Meta::List(syn::MetaList { path, .. }))
+                if path.is_ident("packed") =>
+            {
+                Some(path)
+            }
+            _ => None,
+        })
 }
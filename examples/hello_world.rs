@@ -1,47 +1,153 @@
 use dynamite::*;
 
+use std::sync::RwLock;
+
 /// A Rust function we want to be able to call from scripting. To make this function callable from
 /// scripting languages we can annotate it with the `#[stockpile_funciton]` macro, which creates
 /// script bindings and adds it to the Dynamite stockpile.
 ///
 /// The method will be accessible to scripts under the path `[module_name]::[function_name]`, or
 /// specifically, in this case, `hello_world::rust_func`
+///
+/// Arguments may be taken by reference or by value. Because the function returns an owned value,
+/// the caller takes ownership of the returned `i32` and must give it back to the stockpile with
+/// `free_value` once it is done with it.
 #[stockpile_function]
-fn rust_func(a: &i32, b: &i32) -> &'static i32 {
+fn rust_func(a: i32, b: i32) -> i32 {
     println!("Hello from Rust!! Computing: {} + {}", a, b);
 
-    let value = Box::new(a + b);
-    let ptr = Box::leak(value);
+    a + b
+}
+
+/// Functions may also return a `Result` or an `Option`. Errors are passed to the caller as a
+/// `CallError::Script`, and `None` is returned as a null pointer.
+#[stockpile_function]
+fn checked_div(a: i32, b: i32) -> Result<i32, String> {
+    a.checked_div(b)
+        .ok_or_else(|| format!("Cannot divide {} by {}", a, b))
+}
+
+/// Constants are embedded in the scripting API, so scripts can read them without calling into Rust
+#[stockpile_const]
+const VERSION: &str = "0.1.0";
+
+/// Globals are read and set through the stockpile. Wrapping a global in a `RwLock` or `Mutex`
+/// allows scripts to set it.
+#[stockpile_global]
+static DELTA: RwLock<f32> = RwLock::new(0.);
+
+/// Any other global is read-only
+#[stockpile_global]
+static GRAVITY: f32 = 9.81;
 
-    ptr
+/// A struct that we want to be visible to scripts. Deriving `HasScriptType` adds the struct's
+/// definition, including its layout and the offsets of its fields, to the Dynamite stockpile under
+/// the path `hello_world::Position`.
+#[derive(HasScriptType)]
+#[repr(C)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+/// Enums can be made visible to scripts the same way, as long as they have a `#[repr(C)]` or
+/// integer representation. Both C-like enums and enums with fields are supported.
+#[derive(HasScriptType)]
+#[repr(u8)]
+#[allow(dead_code)] // The variants are only created by scripts
+enum Shape {
+    Point,
+    Circle { radius: f32 },
+    Rectangle(f32, f32),
+}
+
+/// Methods can be made available to scripts with the `#[stockpile_impl]` macro. They are added to
+/// the definition of `Position` and can be called under the path `hello_world::Position::[method]`.
+#[stockpile_impl]
+impl Position {
+    /// Create a new position
+    fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// Get the distance of the position from the origin
+    fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    /// Move the position by the given offset
+    fn translate(&mut self, x: f32, y: f32) {
+        self.x += x;
+        self.y += y;
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize dynamite
     let mut dynamite = Dynamite::new();
 
+    // Validate every call against the scripting API so that calls with the wrong number of
+    // arguments return an error instead of reading invalid memory
+    dynamite.set_checked_calls(true);
+
     // Add our native Rust adapter
     dynamite.add_stockpile()?;
 
     // Load langauge adapter ( relatively safe, but still unsafe because dynamic libraries could do
     // _anything_ 👀 )
-    unsafe {
-        dynamite.load_dynamic_library_language_adapter("./target/debug/libdynamite_python.so")?
+    let python = unsafe {
+        dynamite.load_dynamic_library_language_adapter(
+            "./target/debug/libdynamite_python.so",
+            "python",
+        )?
     };
 
     // Print discovered api
-    dbg!(dynamite.get_full_api());
+    dbg!(dynamite.get_full_api()?);
 
     // Call a function provided by the language adapter ( just assuming for this example that we
-    // know ahead of time that this function exists, it would error if it didn't ). This is also
-    // unsafe because your language adapter could mis-behave.
-    let arg1 = &42f32;
-    unsafe {
-        dynamite.call_function(
-            &"python::test_function".to_string(),
-            &[arg1 as *const f32 as *const Void],
-        );
+    // know ahead of time that this function exists, it would return a `CallError` if it didn't ).
+    // The argument types are checked against the function definition before it is called.
+    dynamite.call(
+        &"python::test_function".into(),
+        &ArgBuffer::new().with(42f32),
+    )?;
+
+    // Methods are called like functions, with the instance as the first argument
+    let length: f32 = dynamite
+        .call(
+            &"hello_world::Position::length".into(),
+            &ArgBuffer::new().with(Position::new(3., 4.)),
+        )?
+        .get()?;
+    println!("Position length: {}", length);
+
+    // Constants can be read straight from the API, and globals are accessed through the adapter
+    // that provides them
+    println!(
+        "Version: {:?}",
+        dynamite.get_constant("hello_world::VERSION")
+    );
+    dynamite.write_global(&"hello_world::DELTA".into(), 0.016f32)?;
+    println!(
+        "Delta: {}",
+        dynamite.read_global::<f32>(&"hello_world::DELTA".into())?
+    );
+    println!(
+        "Gravity: {}",
+        dynamite.read_global::<f32>(&"hello_world::GRAVITY".into())?
+    );
+
+    // Errors returned by Rust functions are returned from `call` as script errors
+    if let Err(error) = dynamite.call(
+        &"hello_world::checked_div".into(),
+        &ArgBuffer::new().with(1i32).with(0i32),
+    ) {
+        println!("Division failed: {}", error);
     }
 
+    // Adapters can be removed again, which shuts them down and unloads their library
+    dynamite.remove_language_adapter(python)?;
+
     Ok(())
 }
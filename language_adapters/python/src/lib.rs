@@ -1,5 +1,4 @@
 use dynamite::*;
-use std::collections::HashMap;
 
 /// The Dynamite Python language adapter
 #[language_adapter]
@@ -20,14 +19,11 @@ impl LanguageAdapter for PythonAdapter {
         components.insert(
             "python::test_function".into(),
             ScriptType::Function(FunctionDefinition {
-                arguments: {
-                    let mut h = HashMap::new();
-
-                    h.insert("number".into(), "std::f32".into());
-
-                    h
-                },
+                arguments: vec![FunctionArgument::new("number", f32::script_path())],
                 return_type: None,
+                return_ownership: ReturnOwnership::Borrowed,
+                fallible: false,
+                optional_return: false,
             }),
         );
 
@@ -40,30 +36,75 @@ impl LanguageAdapter for PythonAdapter {
         host_functions: &dyn HostFunctions,
         path: &str,
         args: &[*const dynamite::Void],
-    ) -> *const dynamite::Void {
-        if path == "python::test_function" {
+    ) -> Result<*const dynamite::Void, CallError> {
+        let slot = self
+            .resolve_function(host_functions, path)?
+            .ok_or_else(|| CallError::NotFound(path.into()))?;
+
+        self.call_slot(host_functions, slot, args)
+    }
+
+    /// Get the slot of a function provided by this adapter
+    fn resolve_function(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        path: &str,
+    ) -> Result<Option<u64>, CallError> {
+        match path {
+            "python::test_function" => Ok(Some(0)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Call a function provided by this adapter by its slot
+    unsafe fn call_slot(
+        &self,
+        host_functions: &dyn HostFunctions,
+        slot: u64,
+        args: &[*const dynamite::Void],
+    ) -> Result<*const dynamite::Void, CallError> {
+        if slot == 0 {
             let arg1 = args[0];
 
             let number = &*(arg1 as *const f32);
 
             println!("The number is: {}", number);
 
-            dbg!(host_functions.get_full_api());
+            dbg!(host_functions.get_full_api()?);
 
             let a = &32;
             let b = &44;
 
-            let ret = host_functions.call_function(
-                &"hello_world::rust_func".to_string(),
+            // Resolve the function once so that it can be called without looking up its path every
+            // time
+            let path = TypePath::from("hello_world::rust_func");
+            let handle = host_functions.resolve_function(&path)?;
+            let ret = host_functions.call_handle(
+                handle,
                 &[
                     a as *const i32 as *const Void,
                     b as *const i32 as *const Void,
                 ],
-            ) as *const i32;
+            )?;
 
-            println!("Got number back: {}", &*ret);
+            println!("Got number back: {}", &*(ret as *const i32));
+
+            // We own the returned value so we have to free it now that we are done with it
+            host_functions.free_value(handle.symbol(), ret)?;
+
+            Ok(std::ptr::null())
+        } else {
+            Err(CallError::AdapterError(format!(
+                "No function in slot {}",
+                slot
+            )))
         }
+    }
+
+    /// Shut down the adapter before it is unloaded
+    fn shutdown(&self, _host_functions: &dyn HostFunctions) -> Result<(), CallError> {
+        println!("Shutting down the Python adapter");
 
-        std::ptr::null()
+        Ok(())
     }
 }
@@ -5,37 +5,72 @@ use safer_ffi::prelude::*;
 
 use ::std::{
     any::{Any, TypeId},
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
     sync::Arc,
 };
 
-use crate::Erased;
+use crate::{DataLayout, DataType, HasScriptType, ScriptType, StructDefinition, TypePath, Void};
 
 /// VTable used to keep track of script type bindings
+///
+/// The vtable is `#[repr(C)]` and only contains `extern "C"` functions so that an [`FFIObj`]
+/// created by one language adapter can be cloned and dropped by another one.
 #[derive_ReprC]
 #[ReprC::opaque]
+#[repr(C)]
 pub struct VTable {
-    type_id: fn() -> TypeId,
-    clone_arc: unsafe fn(*const Erased) -> FFIObj,
-    drop_arc: unsafe fn(*const Erased),
+    type_id: extern "C" fn() -> u64,
+    clone_arc: unsafe extern "C" fn(*const Void) -> FFIObj,
+    drop_arc: unsafe extern "C" fn(*const Void),
 }
 
 /// A ref-counted pointer that can be sent over FFI
+///
+/// An `FFIObj` is an opaque handle to a Rust value. Any language adapter can clone it, drop it, or
+/// downcast it back to the Rust type that it was created from. In the scripting API it is
+/// represented by a [`ScriptType::Handle`].
+///
+/// # Example
+///
+/// ```
+/// # use dynamite::FFIObj;
+/// let obj = FFIObj::new(String::from("hello"));
+/// let clone = obj.clone();
+///
+/// assert_eq!(clone.downcast_ref::<String>().unwrap(), "hello");
+/// assert!(clone.downcast_ref::<u32>().is_none());
+/// ```
 #[derive_ReprC]
 #[repr(C)]
 pub struct FFIObj {
-    ptr: *const Erased,
+    ptr: *const Void,
     vtable: &'static VTable,
 }
 
+/// Get a hash of the [`TypeId`] of `T` that can be returned from an `extern "C"` function
+fn type_id_hash<T: Any>() -> u64 {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<T>().hash(&mut hasher);
+    hasher.finish()
+}
+
 trait HasVTable
 where
     Self: Sized + Any + Send + Sync + 'static,
 {
     const VTABLE: VTable = VTable {
-        type_id: || TypeId::of::<Self>(),
+        type_id: {
+            extern "C" fn type_id<T: Any>() -> u64 {
+                type_id_hash::<T>()
+            }
+            type_id::<Self>
+        },
 
         drop_arc: {
-            unsafe fn drop_arc<T: Any + Send + Sync + 'static>(ptr: *const Erased) {
+            unsafe extern "C" fn drop_arc<T: Any + Send + Sync + 'static>(ptr: *const Void) {
                 let ptr: *const T = ptr.cast();
                 drop::<Arc<T>>(Arc::from_raw(ptr))
             }
@@ -43,10 +78,12 @@ where
         },
 
         clone_arc: {
-            unsafe fn clone_arc<T: Any + Send + Sync + 'static>(ptr: *const Erased) -> FFIObj {
+            unsafe extern "C" fn clone_arc<T: Any + Send + Sync + 'static>(
+                ptr: *const Void,
+            ) -> FFIObj {
                 let ptr: *const T = ptr.cast();
-                let arc_ref: &Arc<T> = &*::core::mem::ManuallyDrop::new(Arc::<T>::from_raw(ptr));
-                let owned_clone: Arc<T> = Arc::clone(arc_ref);
+                let arc = ::core::mem::ManuallyDrop::new(Arc::<T>::from_raw(ptr));
+                let owned_clone: Arc<T> = Arc::clone(&arc);
                 FFIObj {
                     ptr: Arc::into_raw(owned_clone).cast(),
                     vtable: &T::VTABLE,
@@ -57,7 +94,7 @@ where
     };
 }
 
-impl<T: ?Sized> HasVTable for T where Self: Sized + Any + Send + Sync + 'static {}
+impl<T> HasVTable for T where Self: Sized + Any + Send + Sync + 'static {}
 
 impl FFIObj {
     /// Create a new FFI compatible pointer to the given type
@@ -77,8 +114,9 @@ impl FFIObj {
         }
     }
 
-    fn is<T: Any>(self: &'_ FFIObj) -> bool {
-        (self.vtable.type_id)() == ::core::any::TypeId::of::<T>()
+    /// Check whether the FFI object points to a value of type `T`
+    pub fn is<T: Any>(self: &'_ FFIObj) -> bool {
+        (self.vtable.type_id)() == type_id_hash::<T>()
     }
 }
 
@@ -96,3 +134,132 @@ impl Drop for FFIObj {
 
 unsafe impl Send for FFIObj where Arc<dyn Any + Send + Sync + 'static>: Send {}
 unsafe impl Sync for FFIObj where Arc<dyn Any + Send + Sync + 'static>: Sync {}
+
+impl HasScriptType for FFIObj {
+    fn script_type() -> ScriptType {
+        ScriptType::Handle
+    }
+
+    fn script_path() -> TypePath {
+        "dynamite::FFIObj".into()
+    }
+}
+
+/// An owned, fixed-size buffer that can be sent over FFI
+///
+/// Language adapters may use different allocators, so a `ScriptVec` keeps a pointer to the
+/// `extern "C"` function used to free it. This means that it can be dropped by any adapter, and its
+/// memory is always released by the allocator that allocated it. In the scripting API it is
+/// represented by a [`DataType::Vec`].
+///
+/// A `ScriptVec` can't grow, because there is no way to reallocate its memory with the allocator
+/// that allocated it. Its elements can be modified in place, and adapters that need to add or
+/// remove elements must build a new vector, such as by converting it to a [`Vec`] with
+/// [`to_vec`][slice::to_vec] and converting that back into a `ScriptVec`.
+///
+/// # Example
+///
+/// ```
+/// # use dynamite::ScriptVec;
+/// let vec = ScriptVec::from(vec![1, 2, 3]);
+///
+/// assert_eq!(vec.len(), 3);
+/// assert_eq!(&vec[..], &[1, 2, 3]);
+/// ```
+#[repr(C)]
+pub struct ScriptVec<T> {
+    ptr: *mut T,
+    len: usize,
+    capacity: usize,
+    free: unsafe extern "C" fn(ptr: *mut T, len: usize, capacity: usize),
+}
+
+impl<T> ScriptVec<T> {
+    /// Create a new, empty vector
+    pub fn new() -> Self {
+        Vec::new().into()
+    }
+
+    /// Get the elements of the vector as a slice
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Get the elements of the vector as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<T> From<Vec<T>> for ScriptVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        unsafe extern "C" fn free<T>(ptr: *mut T, len: usize, capacity: usize) {
+            drop(Vec::from_raw_parts(ptr, len, capacity));
+        }
+
+        let mut vec = ManuallyDrop::new(vec);
+        Self {
+            ptr: vec.as_mut_ptr(),
+            len: vec.len(),
+            capacity: vec.capacity(),
+            free: free::<T>,
+        }
+    }
+}
+
+impl<T> Default for ScriptVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for ScriptVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for ScriptVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone> Clone for ScriptVec<T> {
+    fn clone(&self) -> Self {
+        self.to_vec().into()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for ScriptVec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for ScriptVec<T> {
+    fn drop(&mut self) {
+        unsafe { (self.free)(self.ptr, self.len, self.capacity) }
+    }
+}
+
+unsafe impl<T: Send> Send for ScriptVec<T> {}
+unsafe impl<T: Sync> Sync for ScriptVec<T> {}
+
+impl<T: HasScriptType> HasScriptType for ScriptVec<T> {
+    fn script_type() -> ScriptType {
+        ScriptType::Struct(StructDefinition {
+            layout: DataLayout::of::<Self>(),
+            component_type: DataType::Vec {
+                element: T::script_path(),
+            },
+            method_definitions: vec![],
+        })
+    }
+
+    fn script_path() -> TypePath {
+        format!("std::Vec<{}>", T::script_path()).into()
+    }
+}
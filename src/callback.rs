@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use crate::{
+    CCallResult, CallError, DataLayout, DataType, FunctionArgument, FunctionDefinition,
+    HasScriptType, ReturnOwnership, ScriptType, StructDefinition, TypePath, Void,
+};
+
+use safer_ffi::prelude::*;
+
+/// A function provided by a language adapter that can be called by any other adapter
+///
+/// Adapters register callbacks, such as script closures, with
+/// [`HostFunctions::register_callback`][crate::HostFunctions::register_callback] and get back a
+/// [`CallbackHandle`] that can be passed to functions in the scripting API. The host keeps track of
+/// how many references there are to the callback and calls `release` once there are none left.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CCallback {
+    /// Adapter specific data used to identify the function, such as a pointer to a script closure
+    pub user_data: *const Void,
+
+    /// Call the function. The `args` are pointers to the argument values, the same as for
+    /// [`LanguageAdapter::call_function`][crate::LanguageAdapter::call_function].
+    pub call: unsafe extern "C" fn(
+        user_data: *const Void,
+        args: c_slice::Ref<*const Void>,
+    ) -> CCallResult,
+
+    /// Free a value returned by the function. Callbacks always return
+    /// [`OwnedByCaller`][ReturnOwnership::OwnedByCaller] values.
+    pub free_value: unsafe extern "C" fn(user_data: *const Void, value: *const Void),
+
+    /// Release the `user_data` once the host no longer references the callback
+    pub release: unsafe extern "C" fn(user_data: *const Void),
+}
+
+/// A handle to a callback registered with the Dynamite host
+///
+/// A handle is only a reference to the callback: it must be retained with
+/// [`HostFunctions::retain_callback`][crate::HostFunctions::retain_callback] to keep the callback
+/// alive, and every retain, as well as the registration itself, must be balanced by a
+/// [`HostFunctions::release_callback`][crate::HostFunctions::release_callback]. Callbacks passed as
+/// arguments are only borrowed for the duration of the call.
+#[repr(C)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackHandle {
+    /// The id of the callback in the host
+    pub id: u64,
+}
+
+/// A [`CallbackHandle`] to a callback with the signature `F`
+///
+/// `F` is a function pointer type such as `Callback<fn(f32, u32) -> bool>`, which is used to
+/// describe the callback in the [`ScriptApi`][crate::ScriptApi] under a path such as
+/// `std::Callback<fn(std::f32, std::u32) -> std::bool>`. The callback is always called with
+/// pointers to its arguments.
+#[repr(transparent)]
+pub struct Callback<F> {
+    handle: CallbackHandle,
+    _phantom: PhantomData<F>,
+}
+
+impl<F> Callback<F> {
+    /// Get the handle to the callback
+    pub fn handle(&self) -> CallbackHandle {
+        self.handle
+    }
+}
+
+impl<F> From<CallbackHandle> for Callback<F> {
+    fn from(handle: CallbackHandle) -> Self {
+        Self {
+            handle,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F> Clone for Callback<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for Callback<F> {}
+
+impl<F> std::fmt::Debug for Callback<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Callback").field(&self.handle).finish()
+    }
+}
+
+/// Create the [`ScriptType`] of a callback with the given definition
+fn callback_script_type(definition: FunctionDefinition) -> ScriptType {
+    ScriptType::Struct(StructDefinition {
+        layout: DataLayout::of::<CallbackHandle>(),
+        component_type: DataType::Callback(definition),
+        method_definitions: vec![],
+    })
+}
+
+macro_rules! impl_callback_type {
+    ($($arg:ident),*) => {
+        impl<$($arg: HasScriptType,)* R: HasScriptType> HasScriptType for Callback<fn($($arg),*) -> R> {
+            fn script_type() -> ScriptType {
+                // Unit returns are treated the same as functions that don't return anything
+                let return_type = Some(R::script_path()).filter(|path| path != "std::unit");
+
+                callback_script_type(FunctionDefinition {
+                    arguments: vec![$(
+                        FunctionArgument::new(stringify!($arg).to_lowercase(), $arg::script_path())
+                    ),*],
+                    return_ownership: if return_type.is_some() {
+                        ReturnOwnership::OwnedByCaller
+                    } else {
+                        ReturnOwnership::Borrowed
+                    },
+                    return_type,
+                    fallible: true,
+                    optional_return: false,
+                })
+            }
+
+            fn script_path() -> TypePath {
+                let arguments: Vec<String> = vec![$($arg::script_path().into()),*];
+                format!("std::Callback<fn({}) -> {}>", arguments.join(", "), R::script_path()).into()
+            }
+        }
+    };
+}
+
+impl_callback_type!();
+impl_callback_type!(A);
+impl_callback_type!(A, B);
+impl_callback_type!(A, B, C);
+impl_callback_type!(A, B, C, D);
+impl_callback_type!(A, B, C, D, E);
+impl_callback_type!(A, B, C, D, E, F);
+
+/// A callback registered with a Dynamite host
+#[derive(Clone, Copy)]
+pub(crate) struct RegisteredCallback {
+    pub(crate) callback: CCallback,
+    /// The adapter instance that registered the callback, or `None` if it was registered by the
+    /// host
+    pub(crate) owner: Option<u32>,
+}
+
+/// The callbacks registered with a Dynamite host and their reference counts
+#[derive(Default)]
+pub(crate) struct CallbackTable {
+    next_id: u64,
+    callbacks: HashMap<u64, (RegisteredCallback, usize)>,
+    /// The ids of the callbacks whose adapter has been removed
+    revoked: HashSet<u64>,
+}
+
+impl CallbackTable {
+    /// Add a callback with a reference count of one
+    pub(crate) fn register(&mut self, callback: CCallback, owner: Option<u32>) -> CallbackHandle {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.callbacks
+            .insert(id, (RegisteredCallback { callback, owner }, 1));
+
+        CallbackHandle { id }
+    }
+
+    /// Get a callback and increment its reference count, which must be decremented again with
+    /// [`CallbackTable::release`] once the callback is no longer used
+    pub(crate) fn acquire(
+        &mut self,
+        handle: CallbackHandle,
+    ) -> Result<RegisteredCallback, CallError> {
+        let error = self.missing(handle);
+        let (callback, count) = self.callbacks.get_mut(&handle.id).ok_or(error)?;
+        *count += 1;
+
+        Ok(*callback)
+    }
+
+    /// Increment the reference count of a callback
+    pub(crate) fn retain(&mut self, handle: CallbackHandle) -> Result<(), CallError> {
+        let error = self.missing(handle);
+        let (_, count) = self.callbacks.get_mut(&handle.id).ok_or(error)?;
+        *count += 1;
+
+        Ok(())
+    }
+
+    /// Decrement the reference count of a callback, returning the callback if it must be released
+    pub(crate) fn release(
+        &mut self,
+        handle: CallbackHandle,
+    ) -> Result<Option<RegisteredCallback>, CallError> {
+        let error = self.missing(handle);
+        let (callback, count) = self.callbacks.get_mut(&handle.id).ok_or(error)?;
+        *count -= 1;
+
+        if *count == 0 {
+            let callback = *callback;
+            self.callbacks.remove(&handle.id);
+            Ok(Some(callback))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Remove every callback registered by an adapter instance, returning the callbacks so that
+    /// they can be released
+    ///
+    /// Handles to the removed callbacks return [`CallError::InvalidCallbackHandle`] from then on.
+    pub(crate) fn revoke_owner(&mut self, owner: u32) -> Vec<CCallback> {
+        let ids = self
+            .callbacks
+            .iter()
+            .filter(|(_, (callback, _))| callback.owner == Some(owner))
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+
+        let mut callbacks = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some((callback, _)) = self.callbacks.remove(&id) {
+                callbacks.push(callback.callback);
+            }
+            self.revoked.insert(id);
+        }
+
+        callbacks
+    }
+
+    /// Get the error for a handle that is not in the table
+    fn missing(&self, handle: CallbackHandle) -> CallError {
+        if self.revoked.contains(&handle.id) {
+            CallError::InvalidCallbackHandle(handle.id)
+        } else {
+            CallError::CallbackNotFound(handle.id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn call(
+        _user_data: *const Void,
+        _args: c_slice::Ref<*const Void>,
+    ) -> CCallResult {
+        Ok(std::ptr::null()).into()
+    }
+
+    unsafe extern "C" fn free_value(_user_data: *const Void, _value: *const Void) {}
+
+    unsafe extern "C" fn release(_user_data: *const Void) {}
+
+    fn callback() -> CCallback {
+        CCallback {
+            user_data: std::ptr::null(),
+            call,
+            free_value,
+            release,
+        }
+    }
+
+    #[test]
+    fn callbacks_are_released_with_their_last_reference() {
+        let mut table = CallbackTable::default();
+        let handle = table.register(callback(), None);
+
+        table.retain(handle).unwrap();
+        table.acquire(handle).unwrap();
+        assert!(table.release(handle).unwrap().is_none());
+        assert!(table.release(handle).unwrap().is_none());
+        assert!(table.release(handle).unwrap().is_some());
+
+        assert!(matches!(
+            table.release(handle),
+            Err(CallError::CallbackNotFound(id)) if id == handle.id
+        ));
+    }
+
+    #[test]
+    fn revoking_an_owner_only_removes_its_callbacks() {
+        let mut table = CallbackTable::default();
+        let host = table.register(callback(), None);
+        let first = table.register(callback(), Some(1));
+        let second = table.register(callback(), Some(2));
+        table.retain(first).unwrap();
+
+        // Revoked callbacks are returned once, however many references there are to them
+        assert_eq!(table.revoke_owner(1).len(), 1);
+        assert!(table.revoke_owner(1).is_empty());
+
+        assert!(matches!(
+            table.retain(first),
+            Err(CallError::InvalidCallbackHandle(id)) if id == first.id
+        ));
+        assert!(matches!(
+            table.release(first),
+            Err(CallError::InvalidCallbackHandle(_))
+        ));
+        table.acquire(host).unwrap();
+        table.acquire(second).unwrap();
+    }
+
+    #[test]
+    fn handles_are_not_reused() {
+        let mut table = CallbackTable::default();
+        let first = table.register(callback(), None);
+        table.release(first).unwrap();
+        let second = table.register(callback(), None);
+
+        assert_ne!(first, second);
+        assert!(matches!(
+            table.retain(first),
+            Err(CallError::CallbackNotFound(_))
+        ));
+    }
+}
@@ -1,8 +1,17 @@
 //! Structs and traits for defining language adapters
 
-use std::ffi::OsStr;
+use std::{
+    cell::RefCell,
+    ffi::OsStr,
+    fmt,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
-use crate::{Dynamite, ScriptApi, TypePath, Void};
+use crate::{
+    CCallback, CallError, CallbackHandle, Dynamite, DynamiteError, FunctionHandle, ScriptApi,
+    SymbolId, TypePath, Void,
+};
 
 /// Type implementing this trait can be loaded as dynamite language adapters wgeb
 pub trait LanguageAdapter {
@@ -10,12 +19,166 @@ pub trait LanguageAdapter {
     fn get_api(&self, host_functions: &dyn HostFunctions) -> ScriptApi;
 
     /// Call a function provided by the language adapter
+    ///
+    /// Returns [`CallError::NotFound`] if the adapter does not provide a function at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The `args` must point to valid values of the types expected by the function's
+    /// [`FunctionDefinition`][crate::FunctionDefinition].
     unsafe fn call_function(
         &self,
         host_functions: &dyn HostFunctions,
         path: &str,
         args: &[*const Void],
-    ) -> *const Void;
+    ) -> Result<*const Void, CallError>;
+
+    /// Resolve the function at `path` to a slot that can be passed to
+    /// [`LanguageAdapter::call_slot`]
+    ///
+    /// Slots let the adapter skip looking up the function by its path when it is called through a
+    /// [`FunctionHandle`]. The default implementation returns `None`, in which case calls through
+    /// handles are made with [`LanguageAdapter::call_function`] instead.
+    fn resolve_function(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        _path: &str,
+    ) -> Result<Option<u64>, CallError> {
+        Ok(None)
+    }
+
+    /// Call the function in a slot returned by [`LanguageAdapter::resolve_function`]
+    ///
+    /// Adapters that return slots must implement this; the default implementation returns an
+    /// error.
+    ///
+    /// # Safety
+    ///
+    /// The `args` must point to valid values of the types expected by the function's
+    /// [`FunctionDefinition`][crate::FunctionDefinition].
+    unsafe fn call_slot(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        slot: u64,
+        _args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        Err(CallError::AdapterError(format!(
+            "Adapter does not provide function slot {}",
+            slot
+        )))
+    }
+
+    /// Free a value returned by the function at `path`
+    ///
+    /// This is called for values returned by functions with a [`ReturnOwnership::OwnedByCaller`]
+    /// return, once the caller is done with the value. Adapters that provide such functions must
+    /// implement this; the default implementation returns an error.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be a pointer returned by a call to the function at `path` on this adapter and
+    /// must not be used after it has been freed.
+    ///
+    /// [`ReturnOwnership::OwnedByCaller`]: crate::ReturnOwnership::OwnedByCaller
+    unsafe fn free_value(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        path: &str,
+        _value: *const Void,
+    ) -> Result<(), CallError> {
+        Err(CallError::AdapterError(format!(
+            "Adapter cannot free values returned by `{}`",
+            path
+        )))
+    }
+
+    /// Get the value of the [`ScriptType::Global`] at `path`
+    ///
+    /// The adapter must write a copy of the value to `out`, which the caller then owns. The default
+    /// implementation returns [`CallError::NotFound`].
+    ///
+    /// # Safety
+    ///
+    /// `out` must be valid for writes of a value with the layout of the global's type.
+    ///
+    /// [`ScriptType::Global`]: crate::ScriptType::Global
+    unsafe fn get_global(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        path: &str,
+        _out: *mut Void,
+    ) -> Result<(), CallError> {
+        Err(CallError::NotFound(path.into()))
+    }
+
+    /// Set the value of the [`ScriptType::Global`] at `path`
+    ///
+    /// The adapter must copy the value from `value`, which is still owned by the caller. The
+    /// default implementation returns [`CallError::NotFound`].
+    ///
+    /// # Safety
+    ///
+    /// `value` must point to a valid value of the global's type.
+    ///
+    /// [`ScriptType::Global`]: crate::ScriptType::Global
+    unsafe fn set_global(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        path: &str,
+        _value: *const Void,
+    ) -> Result<(), CallError> {
+        Err(CallError::NotFound(path.into()))
+    }
+
+    /// Shut the adapter down before it is removed from the host
+    ///
+    /// This is called by [`Dynamite::remove_language_adapter`] before the adapter is dropped, while
+    /// it can still call host functions, such as to release the callbacks that it holds. The
+    /// default implementation does nothing.
+    fn shutdown(&self, _host_functions: &dyn HostFunctions) -> Result<(), CallError> {
+        Ok(())
+    }
+
+    /// Reload the adapter's scripts, such as after they have been changed on disk
+    ///
+    /// This is called by [`Dynamite::reload_scripts`], which gets the adapter's new API with
+    /// [`LanguageAdapter::get_api`] afterwards. If reloading fails, the adapter must keep its old
+    /// scripts. The default implementation returns an error.
+    fn reload_scripts(&self, _host_functions: &dyn HostFunctions) -> Result<(), CallError> {
+        Err(CallError::AdapterError(
+            "Adapter does not support reloading scripts".into(),
+        ))
+    }
+
+    /// Save the state of the adapter's scripts so that it can be restored after a reload
+    ///
+    /// The state is only ever given back to [`LanguageAdapter::restore_state`], so it may be in any
+    /// format. The default implementation returns an empty state.
+    fn serialize_state(&self, _host_functions: &dyn HostFunctions) -> Result<Vec<u8>, CallError> {
+        Ok(Vec::new())
+    }
+
+    /// Restore the state saved by [`LanguageAdapter::serialize_state`] after a reload
+    ///
+    /// When a library adapter is reloaded, the state is given to the new instance of the adapter,
+    /// which may be a different version of it. The default implementation ignores the state.
+    fn restore_state(
+        &self,
+        _host_functions: &dyn HostFunctions,
+        _state: &[u8],
+    ) -> Result<(), CallError> {
+        Ok(())
+    }
+}
+
+/// The id of a language adapter added to a [`Dynamite`] host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdapterId(pub(crate) usize);
+
+impl fmt::Display for AdapterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 pub trait DynamicLibLanguageAdapter {
@@ -27,31 +190,153 @@ pub trait DynamicLibLanguageAdapter {
 pub struct LoadedDynamicLibLanguageAdapter {
     /// the container for the adapter's C API
     api: Container<LanguageAdapterCApi>,
+    /// The [`ScriptApi`] reported by the adapter when it was loaded or its scripts were reloaded
+    script_api: RefCell<ScriptApi>,
+    /// The metadata exported by the adapter
+    metadata: AdapterMetadata,
+    /// The copy of the library that was loaded, if it was copied. This must come after `api` so
+    /// that the copy is deleted after the library is unloaded.
+    _copy: Option<LibraryCopy>,
+}
+
+/// A copy of a dynamic library that is deleted when it is dropped
+struct LibraryCopy(PathBuf);
+
+impl Drop for LibraryCopy {
+    fn drop(&mut self) {
+        // The copy is only needed while the library is loaded, so there is nothing to do if it
+        // cannot be deleted
+        let _ = std::fs::remove_file(&self.0);
+    }
 }
 
-impl<'a> LoadedDynamicLibLanguageAdapter {
+impl LoadedDynamicLibLanguageAdapter {
     /// Load a dynamic lib language adapter
+    ///
+    /// The adapter's API is requested once while loading so that any error or panic in the adapter
+    /// can be reported here instead of when the adapter is added to the [`Dynamite`] host.
+    ///
+    /// Before any of the adapter's functions are called, the library's [`ABI_VERSION`] and
+    /// [`AdapterMetadata`] are checked, and [`DynamiteError::AbiMismatch`] is returned if the
+    /// adapter was built with an incompatible version of Dynamite.
+    ///
+    /// `instance` is given to the adapter when it is initialized and identifies it as the owner of
+    /// the callbacks that it registers.
+    ///
+    /// # Safety
+    ///
+    /// Loading a dynamic library may run arbitrary code, and the library must have been created
+    /// with the [`language_adapter`][crate::language_adapter] macro.
     pub unsafe fn load<P: AsRef<OsStr>>(
         path: P,
         host_functions: CHostFunctionPointers,
-    ) -> Result<Self, dlopen::Error> {
+        dynamite: &Dynamite,
+        instance: u32,
+    ) -> Result<Self, DynamiteError> {
+        // Make sure the library is compatible before loading its API
+        let metadata = read_adapter_metadata(path.as_ref())?;
+
         // Load the dynamic library
         let api: Container<LanguageAdapterCApi> = Container::load(path)?;
 
         // Initialize the adapter
-        api.init_adapter(host_functions);
+        decode_cbor_result::<()>(&api.init_adapter(host_functions, instance))?;
 
-        Ok(Self { api })
+        // Get the adapter's API
+        let script_api =
+            decode_cbor_result(&api.get_api(dynamite as *const Dynamite as *const Void))?;
+
+        Ok(Self {
+            api,
+            script_api: RefCell::new(script_api),
+            metadata,
+            _copy: None,
+        })
+    }
+
+    /// Get the metadata exported by the adapter
+    pub fn metadata(&self) -> &AdapterMetadata {
+        &self.metadata
+    }
+
+    /// Load a dynamic lib language adapter from a copy of the library
+    ///
+    /// The library is copied to a file with a unique name in the temporary directory, because
+    /// loading the same path twice may return the library that is already loaded instead of the
+    /// one on disk. This makes it possible to load a library again after it has been rebuilt. The
+    /// copy is deleted when the adapter is dropped.
+    ///
+    /// # Safety
+    ///
+    /// See [`LoadedDynamicLibLanguageAdapter::load`].
+    pub unsafe fn load_copy<P: AsRef<Path>>(
+        path: P,
+        host_functions: CHostFunctionPointers,
+        dynamite: &Dynamite,
+        instance: u32,
+    ) -> Result<Self, DynamiteError> {
+        static COPY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        // Keep the original file name at the end so that the copy has the same extension
+        let path = path.as_ref();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let copy = LibraryCopy(std::env::temp_dir().join(format!(
+            "dynamite-{}-{}-{}",
+            std::process::id(),
+            COPY_COUNT.fetch_add(1, Ordering::Relaxed),
+            file_name
+        )));
+        std::fs::copy(path, &copy.0)?;
+
+        let mut adapter = Self::load(&copy.0, host_functions, dynamite, instance)?;
+        adapter._copy = Some(copy);
+
+        Ok(adapter)
     }
 }
 
-impl LanguageAdapter for LoadedDynamicLibLanguageAdapter {
-    fn get_api(&self, host_functions: &dyn HostFunctions) -> ScriptApi {
-        let bytes = self
-            .api
-            .get_api(host_functions.as_dynamite() as *const Dynamite as *const Void);
+/// Read the ABI version and metadata of an adapter library and make sure that they are compatible
+/// with this version of Dynamite, without calling any of the adapter's other functions
+unsafe fn read_adapter_metadata(path: &OsStr) -> Result<AdapterMetadata, DynamiteError> {
+    let mismatch = |reason: String| DynamiteError::AbiMismatch {
+        path: PathBuf::from(path),
+        reason,
+    };
+    let library = dlopen::raw::Library::open(path)?;
+
+    // The ABI version is a plain static, so it can be read from a library built with any version
+    let abi_version = library
+        .symbol::<*const u32>(ABI_VERSION_SYMBOL)
+        .map_err(|_| mismatch("the library does not export a Dynamite ABI version".into()))?;
+    if *abi_version != ABI_VERSION {
+        return Err(mismatch(format!(
+            "the library uses ABI version {} but the host uses ABI version {}",
+            *abi_version, ABI_VERSION
+        )));
+    }
+
+    // The ABI versions match, so the metadata function has the signature that we expect
+    let get_metadata = library
+        .symbol::<unsafe extern "C" fn() -> safer_ffi::prelude::repr_c::Vec<u8>>(METADATA_SYMBOL)
+        .map_err(|_| mismatch("the library does not export adapter metadata".into()))?;
+    let metadata: AdapterMetadata = decode_cbor_result(&get_metadata())?;
+    if !metadata.is_compatible() {
+        let supported = match metadata.supported_versions() {
+            Some((min, max)) => format!(">= {}, < {}", min, max),
+            None => metadata.dynamite_version.clone(),
+        };
+        return Err(mismatch(format!(
+            "{} {} supports Dynamite {} but the host uses Dynamite {}",
+            metadata.name, metadata.version, supported, VERSION
+        )));
+    }
+
+    Ok(metadata)
+}
 
-        serde_cbor::from_slice(&bytes).expect("Could not parse CBOR api data from language adapter")
+impl LanguageAdapter for LoadedDynamicLibLanguageAdapter {
+    fn get_api(&self, _host_functions: &dyn HostFunctions) -> ScriptApi {
+        self.script_api.borrow().clone()
     }
 
     unsafe fn call_function(
@@ -59,42 +344,349 @@ impl LanguageAdapter for LoadedDynamicLibLanguageAdapter {
         host_functions: &dyn HostFunctions,
         path: &str,
         args: &[*const Void],
-    ) -> *const Void {
-        self.api.call_function(
+    ) -> Result<*const Void, CallError> {
+        self.api
+            .call_function(
+                host_functions.as_dynamite() as *const Dynamite as *const Void,
+                path.into(),
+                args.into(),
+            )
+            .into_result()
+    }
+
+    fn resolve_function(
+        &self,
+        host_functions: &dyn HostFunctions,
+        path: &str,
+    ) -> Result<Option<u64>, CallError> {
+        decode_cbor_result(&unsafe {
+            self.api.resolve_function(
+                host_functions.as_dynamite() as *const Dynamite as *const Void,
+                path.into(),
+            )
+        })
+    }
+
+    unsafe fn call_slot(
+        &self,
+        host_functions: &dyn HostFunctions,
+        slot: u64,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        self.api
+            .call_slot(
+                host_functions.as_dynamite() as *const Dynamite as *const Void,
+                slot,
+                args.into(),
+            )
+            .into_result()
+    }
+
+    unsafe fn free_value(
+        &self,
+        host_functions: &dyn HostFunctions,
+        path: &str,
+        value: *const Void,
+    ) -> Result<(), CallError> {
+        decode_cbor_result(&self.api.free_value(
             host_functions.as_dynamite() as *const Dynamite as *const Void,
             path.into(),
-            args.into(),
-        )
+            value,
+        ))
+    }
+
+    unsafe fn get_global(
+        &self,
+        host_functions: &dyn HostFunctions,
+        path: &str,
+        out: *mut Void,
+    ) -> Result<(), CallError> {
+        decode_cbor_result(&self.api.get_global(
+            host_functions.as_dynamite() as *const Dynamite as *const Void,
+            path.into(),
+            out,
+        ))
+    }
+
+    unsafe fn set_global(
+        &self,
+        host_functions: &dyn HostFunctions,
+        path: &str,
+        value: *const Void,
+    ) -> Result<(), CallError> {
+        decode_cbor_result(&self.api.set_global(
+            host_functions.as_dynamite() as *const Dynamite as *const Void,
+            path.into(),
+            value,
+        ))
+    }
+
+    fn shutdown(&self, host_functions: &dyn HostFunctions) -> Result<(), CallError> {
+        decode_cbor_result(&unsafe {
+            self.api
+                .shutdown_adapter(host_functions.as_dynamite() as *const Dynamite as *const Void)
+        })
+    }
+
+    fn reload_scripts(&self, host_functions: &dyn HostFunctions) -> Result<(), CallError> {
+        let dynamite = host_functions.as_dynamite() as *const Dynamite as *const Void;
+        decode_cbor_result::<()>(&unsafe { self.api.reload_scripts(dynamite) })?;
+
+        // Get the new API right away so that errors are reported here
+        *self.script_api.borrow_mut() = decode_cbor_result(&unsafe { self.api.get_api(dynamite) })?;
+
+        Ok(())
+    }
+
+    fn serialize_state(&self, host_functions: &dyn HostFunctions) -> Result<Vec<u8>, CallError> {
+        decode_cbor_result(&unsafe {
+            self.api
+                .serialize_state(host_functions.as_dynamite() as *const Dynamite as *const Void)
+        })
+    }
+
+    fn restore_state(
+        &self,
+        host_functions: &dyn HostFunctions,
+        state: &[u8],
+    ) -> Result<(), CallError> {
+        decode_cbor_result(&unsafe {
+            self.api.restore_state(
+                host_functions.as_dynamite() as *const Dynamite as *const Void,
+                state.into(),
+            )
+        })
     }
 }
 
 /// Functions provided by the Dynamite host that can be called from language adapters
+///
+/// The host is not thread safe, so host functions may only be called from the thread that uses the
+/// [`Dynamite`] host, even by adapters that run their own event loop.
 pub trait HostFunctions {
     /// Used internally: returns a reference to the backing [`Dynamite`] instance
     #[doc(hidden)]
     fn as_dynamite(&self) -> &Dynamite;
 
     /// Get the full scripting API as the sum of all language adapters' APIs
-    fn get_full_api(&self) -> ScriptApi;
+    fn get_full_api(&self) -> Result<ScriptApi, CallError>;
 
     /// Call a function provided by the scripting API
-    unsafe fn call_function(&self, path: &TypePath, args: &[*const Void]) -> *const Void;
+    ///
+    /// # Safety
+    ///
+    /// The `args` must point to valid values of the types expected by the function's
+    /// [`FunctionDefinition`][crate::FunctionDefinition].
+    unsafe fn call_function(
+        &self,
+        path: &TypePath,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError>;
+
+    /// Get the [`SymbolId`] of a path in the scripting API
+    ///
+    /// Symbols stay the same for as long as the host is running, so a path only has to be resolved
+    /// once. Returns [`CallError::NotFound`] if there is nothing at `path`.
+    fn resolve_symbol(&self, path: &str) -> Result<SymbolId, CallError>;
+
+    /// Resolve the function at `path` to a [`FunctionHandle`]
+    ///
+    /// Returns [`CallError::NotAFunction`] if there is something other than a function at `path`.
+    fn resolve_function(&self, path: &str) -> Result<FunctionHandle, CallError>;
+
+    /// Call a function through a handle returned by [`HostFunctions::resolve_function`]
+    ///
+    /// # Safety
+    ///
+    /// The `args` must point to valid values of the types expected by the function's
+    /// [`FunctionDefinition`][crate::FunctionDefinition].
+    unsafe fn call_handle(
+        &self,
+        handle: FunctionHandle,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError>;
+
+    /// Free a value returned by the function registered under `symbol`, as described by
+    /// [`ReturnOwnership::OwnedByCaller`]
+    ///
+    /// # Safety
+    ///
+    /// `value` must be a pointer returned by a call to the function and must not be used after it
+    /// has been freed.
+    ///
+    /// [`ReturnOwnership::OwnedByCaller`]: crate::ReturnOwnership::OwnedByCaller
+    unsafe fn free_value(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError>;
+
+    /// Get the value of the global registered under `symbol`, writing a copy of it to `out`
+    ///
+    /// # Safety
+    ///
+    /// `out` must be valid for writes of a value with the layout of the global's type. The caller
+    /// owns the value written to it.
+    unsafe fn get_global(&self, symbol: SymbolId, out: *mut Void) -> Result<(), CallError>;
+
+    /// Set the value of the global registered under `symbol`, copying it from `value`
+    ///
+    /// Returns [`CallError::GlobalNotSettable`] if the global is read-only.
+    ///
+    /// # Safety
+    ///
+    /// `value` must point to a valid value of the global's type.
+    unsafe fn set_global(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError>;
+
+    /// Register a callback so that it can be called by any adapter
+    ///
+    /// The callback starts with a reference count of one, which is owned by the caller. The
+    /// callback belongs to the adapter that registers it, and is released when that adapter is
+    /// removed even if other adapters still hold references to it.
+    fn register_callback(&self, callback: CCallback) -> Result<CallbackHandle, CallError>;
+
+    /// Increment the reference count of a callback
+    fn retain_callback(&self, handle: CallbackHandle) -> Result<(), CallError>;
+
+    /// Decrement the reference count of a callback, releasing it once there are no references left
+    fn release_callback(&self, handle: CallbackHandle) -> Result<(), CallError>;
+
+    /// Call a registered callback
+    ///
+    /// The host holds a reference to the callback for the duration of the call, so the callback
+    /// can't be released while it runs.
+    ///
+    /// # Safety
+    ///
+    /// The `args` must point to valid values of the types expected by the callback.
+    unsafe fn call_callback(
+        &self,
+        handle: CallbackHandle,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError>;
+
+    /// Free a value returned by a callback
+    ///
+    /// # Safety
+    ///
+    /// `value` must be a pointer returned by a call to the callback and must not be used after it
+    /// has been freed.
+    unsafe fn free_callback_value(
+        &self,
+        handle: CallbackHandle,
+        value: *const Void,
+    ) -> Result<(), CallError>;
 }
 
 pub use capi::*;
 use dlopen::wrapper::Container;
 #[allow(missing_docs)]
 mod capi {
-    use crate::{Dynamite, HostFunctions, Void};
+    use crate::{
+        CCallback, CallError, CallbackHandle, Dynamite, FunctionHandle, HostFunctions, SymbolId,
+        Void,
+    };
     use dlopen::wrapper::WrapperApi;
     use safer_ffi::prelude::*;
+    use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+    /// The version of the C API between the Dynamite host and language adapters
+    ///
+    /// This must be incremented whenever the signature of a function in [`LanguageAdapterCApi`] or
+    /// [`CHostFunctionPointers`] changes. The [`language_adapter`][crate::language_adapter] macro
+    /// exports the version that the adapter was built with, and adapters with a different version
+    /// are rejected when they are loaded.
+    pub const ABI_VERSION: u32 = 3;
+
+    /// The version of the Dynamite crate
+    pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+    /// The name of the static holding the [`ABI_VERSION`] of an adapter library
+    pub const ABI_VERSION_SYMBOL: &str = "dynamite_abi_version";
+
+    /// The name of the function returning the [`AdapterMetadata`] of an adapter library, as a CBOR
+    /// serialized `Result<AdapterMetadata, CallError>`
+    pub const METADATA_SYMBOL: &str = "dynamite_adapter_metadata";
+
+    /// Information about a language adapter that is checked before the adapter is loaded
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct AdapterMetadata {
+        /// The name of the adapter's crate
+        pub name: String,
+        /// The version of the adapter's crate
+        pub version: String,
+        /// The version of Dynamite that the adapter was built with
+        pub dynamite_version: String,
+        /// The oldest version of Dynamite that the adapter supports, or `None` to start at the
+        /// oldest version that is semver compatible with `dynamite_version`
+        #[serde(default)]
+        pub min_dynamite_version: Option<String>,
+        /// The first version of Dynamite that the adapter no longer supports, or `None` to stop
+        /// at the first version that is not semver compatible with `dynamite_version`
+        #[serde(default)]
+        pub max_dynamite_version: Option<String>,
+    }
+
+    impl AdapterMetadata {
+        /// Whether the adapter supports this version of Dynamite
+        ///
+        /// The host's [`VERSION`] must be in the range returned by
+        /// [`AdapterMetadata::supported_versions`].
+        pub fn is_compatible(&self) -> bool {
+            match (parse_version(VERSION), self.supported_versions()) {
+                (Some(host), Some((min, max))) => {
+                    parse_version(&min).map(|min| host >= min).unwrap_or(false)
+                        && parse_version(&max).map(|max| host < max).unwrap_or(false)
+                }
+                _ => false,
+            }
+        }
+
+        /// Get the inclusive lower and the exclusive upper bound of the Dynamite versions that the
+        /// adapter supports
+        ///
+        /// Bounds that the adapter doesn't declare default to the range of versions that are
+        /// semver compatible with `dynamite_version`, which means that the left-most non-zero
+        /// component of the versions must be the same: an adapter built with `0.2.5` supports
+        /// `0.2.0` up to, but not including, `0.3.0`. Returns `None` if `dynamite_version` is not a
+        /// valid version.
+        pub fn supported_versions(&self) -> Option<(String, String)> {
+            let version = parse_version(&self.dynamite_version)?;
+            let significant = version.iter().position(|&part| part != 0).unwrap_or(2);
+            let mut min = [0; 3];
+            min[..=significant].copy_from_slice(&version[..=significant]);
+            let mut max = min;
+            max[significant] += 1;
+
+            let format = |[major, minor, patch]: [u64; 3]| format!("{}.{}.{}", major, minor, patch);
+            Some((
+                self.min_dynamite_version
+                    .clone()
+                    .unwrap_or_else(|| format(min)),
+                self.max_dynamite_version
+                    .clone()
+                    .unwrap_or_else(|| format(max)),
+            ))
+        }
+    }
+
+    /// Parse a `major.minor.patch` version, ignoring pre-release and build metadata
+    fn parse_version(version: &str) -> Option<[u64; 3]> {
+        let version = version.split(['-', '+']).next()?;
+        let parts = version
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        match parts.as_slice() {
+            &[major, minor, patch] => Some([major, minor, patch]),
+            _ => None,
+        }
+    }
 
     /// Pointers to the C functions that the host provides for use by the language adapters
     #[repr(C)]
     #[derive(Clone, Copy)]
     pub struct CHostFunctionPointers {
-        /// Get the full CBOR serialized [`ScriptApi`] including components discovered and
-        /// implemented by other language adapters or the dynamite host.
+        /// Get the full [`ScriptApi`] including components discovered and implemented by other
+        /// language adapters or the dynamite host, as a CBOR serialized `Result<ScriptApi,
+        /// CallError>`.
         pub get_full_api: extern "C" fn(dynamite: *const Void) -> repr_c::Vec<u8>,
 
         /// Call a function provided by the scripting API
@@ -102,7 +694,74 @@ mod capi {
             dynamite: *const Void,
             path: str::Ref,
             args: c_slice::Ref<*const Void>,
-        ) -> *const Void,
+        ) -> CCallResult,
+
+        /// Get the symbol of a path. Returns a CBOR serialized `Result<SymbolId, CallError>`.
+        pub resolve_symbol: extern "C" fn(dynamite: *const Void, path: str::Ref) -> repr_c::Vec<u8>,
+
+        /// Resolve a function handle. Returns a CBOR serialized `Result<FunctionHandle,
+        /// CallError>`.
+        pub resolve_function:
+            extern "C" fn(dynamite: *const Void, path: str::Ref) -> repr_c::Vec<u8>,
+
+        /// Call a function provided by the scripting API through a handle
+        pub call_handle: extern "C" fn(
+            dynamite: *const Void,
+            handle: FunctionHandle,
+            args: c_slice::Ref<*const Void>,
+        ) -> CCallResult,
+
+        /// Free a value returned by a function in the scripting API. Returns a CBOR serialized
+        /// `Result<(), CallError>`.
+        pub free_value: extern "C" fn(
+            dynamite: *const Void,
+            symbol: SymbolId,
+            value: *const Void,
+        ) -> repr_c::Vec<u8>,
+
+        /// Get the value of a global. Returns a CBOR serialized `Result<(), CallError>`.
+        pub get_global: extern "C" fn(
+            dynamite: *const Void,
+            symbol: SymbolId,
+            out: *mut Void,
+        ) -> repr_c::Vec<u8>,
+
+        /// Set the value of a global. Returns a CBOR serialized `Result<(), CallError>`.
+        pub set_global: extern "C" fn(
+            dynamite: *const Void,
+            symbol: SymbolId,
+            value: *const Void,
+        ) -> repr_c::Vec<u8>,
+
+        /// Register a callback owned by the adapter instance that was given to `init_adapter`.
+        /// Returns a CBOR serialized `Result<CallbackHandle, CallError>`.
+        pub register_callback: extern "C" fn(
+            dynamite: *const Void,
+            instance: u32,
+            callback: CCallback,
+        ) -> repr_c::Vec<u8>,
+
+        /// Retain a callback. Returns a CBOR serialized `Result<(), CallError>`.
+        pub retain_callback:
+            extern "C" fn(dynamite: *const Void, handle: CallbackHandle) -> repr_c::Vec<u8>,
+
+        /// Release a callback. Returns a CBOR serialized `Result<(), CallError>`.
+        pub release_callback:
+            extern "C" fn(dynamite: *const Void, handle: CallbackHandle) -> repr_c::Vec<u8>,
+
+        /// Call a callback
+        pub call_callback: extern "C" fn(
+            dynamite: *const Void,
+            handle: CallbackHandle,
+            args: c_slice::Ref<*const Void>,
+        ) -> CCallResult,
+
+        /// Free a value returned by a callback. Returns a CBOR serialized `Result<(), CallError>`.
+        pub free_callback_value: extern "C" fn(
+            dynamite: *const Void,
+            handle: CallbackHandle,
+            value: *const Void,
+        ) -> repr_c::Vec<u8>,
     }
 
     // TODO: Unsure of the soundness of this workaround to not being able to derive ReprC through
@@ -124,17 +783,19 @@ mod capi {
     #[repr(C)]
     pub struct RemoteHostFunctions {
         pub dynamite: *const Void,
+        /// The adapter instance that was given to `init_adapter`, which owns the callbacks that
+        /// are registered through these host functions
+        pub instance: u32,
         pub pointers: CHostFunctionPointers,
     }
 
     impl HostFunctions for RemoteHostFunctions {
-        fn get_full_api(&self) -> crate::ScriptApi {
+        fn get_full_api(&self) -> Result<crate::ScriptApi, CallError> {
             // Get the bytes of the API
             let bytes = (self.pointers.get_full_api)(self.dynamite);
 
             // Parse the btyes as a ScriptAPI
-            serde_cbor::from_slice(&bytes)
-                .expect("Could not parse CBOR API definition from language adapter")
+            decode_cbor_result(&bytes)
         }
 
         fn as_dynamite(&self) -> &Dynamite {
@@ -145,27 +806,324 @@ mod capi {
             &self,
             path: &crate::TypePath,
             args: &[*const Void],
-        ) -> *const Void {
+        ) -> Result<*const Void, CallError> {
             (self.pointers.call_function)(self.dynamite, path.as_str().into(), args.into())
+                .into_result()
+        }
+
+        fn resolve_symbol(&self, path: &str) -> Result<SymbolId, CallError> {
+            decode_cbor_result(&(self.pointers.resolve_symbol)(self.dynamite, path.into()))
+        }
+
+        fn resolve_function(&self, path: &str) -> Result<FunctionHandle, CallError> {
+            decode_cbor_result(&(self.pointers.resolve_function)(
+                self.dynamite,
+                path.into(),
+            ))
+        }
+
+        unsafe fn call_handle(
+            &self,
+            handle: FunctionHandle,
+            args: &[*const Void],
+        ) -> Result<*const Void, CallError> {
+            (self.pointers.call_handle)(self.dynamite, handle, args.into()).into_result()
+        }
+
+        unsafe fn free_value(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError> {
+            decode_cbor_result(&(self.pointers.free_value)(self.dynamite, symbol, value))
+        }
+
+        unsafe fn get_global(&self, symbol: SymbolId, out: *mut Void) -> Result<(), CallError> {
+            decode_cbor_result(&(self.pointers.get_global)(self.dynamite, symbol, out))
+        }
+
+        unsafe fn set_global(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError> {
+            decode_cbor_result(&(self.pointers.set_global)(self.dynamite, symbol, value))
+        }
+
+        fn register_callback(&self, callback: CCallback) -> Result<CallbackHandle, CallError> {
+            decode_cbor_result(&(self.pointers.register_callback)(
+                self.dynamite,
+                self.instance,
+                callback,
+            ))
+        }
+
+        fn retain_callback(&self, handle: CallbackHandle) -> Result<(), CallError> {
+            decode_cbor_result(&(self.pointers.retain_callback)(self.dynamite, handle))
+        }
+
+        fn release_callback(&self, handle: CallbackHandle) -> Result<(), CallError> {
+            decode_cbor_result(&(self.pointers.release_callback)(self.dynamite, handle))
+        }
+
+        unsafe fn call_callback(
+            &self,
+            handle: CallbackHandle,
+            args: &[*const Void],
+        ) -> Result<*const Void, CallError> {
+            (self.pointers.call_callback)(self.dynamite, handle, args.into()).into_result()
+        }
+
+        unsafe fn free_callback_value(
+            &self,
+            handle: CallbackHandle,
+            value: *const Void,
+        ) -> Result<(), CallError> {
+            decode_cbor_result(&(self.pointers.free_callback_value)(
+                self.dynamite,
+                handle,
+                value,
+            ))
+        }
+    }
+
+    /// The FFI-safe result of calling a function through the C API
+    #[derive_ReprC]
+    #[repr(C)]
+    pub struct CCallResult {
+        /// The value returned by the function, or null if the call failed
+        pub value: *const Void,
+        /// The CBOR serialized [`CallError`] if the call failed, or empty if the call succeeded
+        pub error: repr_c::Vec<u8>,
+    }
+
+    impl CCallResult {
+        /// Convert the C result back into a Rust [`Result`]
+        pub fn into_result(self) -> Result<*const Void, CallError> {
+            if self.error.is_empty() {
+                Ok(self.value)
+            } else {
+                Err(serde_cbor::from_slice(&self.error).unwrap_or_else(|e| {
+                    CallError::AdapterError(format!("Could not parse CBOR call error: {}", e))
+                }))
+            }
         }
     }
 
+    impl From<Result<*const Void, CallError>> for CCallResult {
+        fn from(result: Result<*const Void, CallError>) -> Self {
+            match result {
+                Ok(value) => CCallResult {
+                    value,
+                    error: Vec::new().into(),
+                },
+                Err(error) => CCallResult {
+                    value: std::ptr::null(),
+                    error: serde_cbor::to_vec(&error)
+                        .unwrap_or_else(|_| SERIALIZATION_ERROR.to_vec())
+                        .into(),
+                },
+            }
+        }
+    }
+
+    /// Serialize a result to CBOR so that it can be returned across the C API
+    ///
+    /// Errors are encoded as [`CallError`]s. Use [`decode_cbor_result`] to parse the result on the
+    /// other side.
+    pub fn encode_cbor_result<T: Serialize>(result: &Result<T, CallError>) -> repr_c::Vec<u8> {
+        serde_cbor::to_vec(result)
+            .or_else(|e| {
+                serde_cbor::to_vec(&Result::<(), _>::Err(CallError::AdapterError(format!(
+                    "Could not serialize result: {}",
+                    e
+                ))))
+            })
+            .unwrap_or_else(|_| SERIALIZATION_ERROR_RESULT.to_vec())
+            .into()
+    }
+
+    /// The CBOR serialized `CallError::AdapterError("Could not serialize the result")`, which is
+    /// returned if not even the error of a result can be serialized
+    const SERIALIZATION_ERROR: &[u8] =
+        b"\xa1\x6cAdapterError\x78\x1eCould not serialize the result";
+
+    /// [`SERIALIZATION_ERROR`] as the error of a CBOR serialized `Result`
+    const SERIALIZATION_ERROR_RESULT: &[u8] =
+        b"\xa1\x63Err\xa1\x6cAdapterError\x78\x1eCould not serialize the result";
+
+    /// Parse a result serialized with [`encode_cbor_result`]
+    pub fn decode_cbor_result<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, CallError> {
+        serde_cbor::from_slice::<Result<T, CallError>>(bytes).unwrap_or_else(|e| {
+            Err(CallError::AdapterError(format!(
+                "Could not parse CBOR result: {}",
+                e
+            )))
+        })
+    }
+
     /// The C API implemented by language adapters
+    ///
+    /// None of these functions may unwind: panics in the adapter must be caught and returned as a
+    /// [`CallError::AdapterPanic`].
     #[derive(WrapperApi)]
     pub struct LanguageAdapterCApi {
-        /// Initialize the language adapter
-        init_adapter: fn(host_functions: CHostFunctionPointers),
+        /// Initialize the language adapter. `instance` identifies this instance of the adapter to
+        /// the host, and must be passed back when the adapter registers callbacks so that the
+        /// callbacks are owned by it. The return value of the function must be a CBOR serialized
+        /// `Result<(), CallError>`.
+        init_adapter: unsafe extern "C" fn(
+            host_functions: CHostFunctionPointers,
+            instance: u32,
+        ) -> repr_c::Vec<u8>,
 
         /// Get a catalog of all of the components discovered by the adapter. The return value of
         /// the function must be a vector of bytes in the CBOR format corresponding to a serialized
-        /// [`ScriptApi`].
-        get_api: fn(dynamite: *const Void) -> safer_ffi::Vec<u8>,
+        /// `Result<ScriptApi, CallError>`.
+        get_api: unsafe extern "C" fn(dynamite: *const Void) -> repr_c::Vec<u8>,
 
         /// Execute a function that is hosted by the language adapter.
-        call_function: fn(
+        call_function: unsafe extern "C" fn(
             dynamite: *const Void,
             path: str::Ref,
             args: c_slice::Ref<*const Void>,
-        ) -> *const Void,
+        ) -> CCallResult,
+
+        /// Resolve a function hosted by the language adapter to a slot. The return value of the
+        /// function must be a CBOR serialized `Result<Option<u64>, CallError>`.
+        resolve_function:
+            unsafe extern "C" fn(dynamite: *const Void, path: str::Ref) -> repr_c::Vec<u8>,
+
+        /// Execute the function in a slot returned by `resolve_function`.
+        call_slot: unsafe extern "C" fn(
+            dynamite: *const Void,
+            slot: u64,
+            args: c_slice::Ref<*const Void>,
+        ) -> CCallResult,
+
+        /// Free a value returned by a function hosted by the language adapter. The return value of
+        /// the function must be a CBOR serialized `Result<(), CallError>`.
+        free_value: unsafe extern "C" fn(
+            dynamite: *const Void,
+            path: str::Ref,
+            value: *const Void,
+        ) -> repr_c::Vec<u8>,
+
+        /// Get the value of a global provided by the language adapter. The return value of the
+        /// function must be a CBOR serialized `Result<(), CallError>`.
+        get_global: unsafe extern "C" fn(
+            dynamite: *const Void,
+            path: str::Ref,
+            out: *mut Void,
+        ) -> repr_c::Vec<u8>,
+
+        /// Set the value of a global provided by the language adapter. The return value of the
+        /// function must be a CBOR serialized `Result<(), CallError>`.
+        set_global: unsafe extern "C" fn(
+            dynamite: *const Void,
+            path: str::Ref,
+            value: *const Void,
+        ) -> repr_c::Vec<u8>,
+
+        /// Shut the language adapter down before the library is unloaded. The return value of the
+        /// function must be a CBOR serialized `Result<(), CallError>`.
+        shutdown_adapter: unsafe extern "C" fn(dynamite: *const Void) -> repr_c::Vec<u8>,
+
+        /// Reload the adapter's scripts. The return value of the function must be a CBOR
+        /// serialized `Result<(), CallError>`.
+        reload_scripts: unsafe extern "C" fn(dynamite: *const Void) -> repr_c::Vec<u8>,
+
+        /// Save the state of the adapter's scripts. The return value of the function must be a
+        /// CBOR serialized `Result<Vec<u8>, CallError>`.
+        serialize_state: unsafe extern "C" fn(dynamite: *const Void) -> repr_c::Vec<u8>,
+
+        /// Restore the state saved by `serialize_state`. The return value of the function must be
+        /// a CBOR serialized `Result<(), CallError>`.
+        restore_state:
+            unsafe extern "C" fn(dynamite: *const Void, state: c_slice::Ref<u8>) -> repr_c::Vec<u8>,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn serialization_error() -> CallError {
+            CallError::AdapterError("Could not serialize the result".into())
+        }
+
+        #[test]
+        fn serialization_errors_match_their_encoding() {
+            let error = serde_cbor::to_vec(&serialization_error()).unwrap();
+            assert_eq!(error, SERIALIZATION_ERROR);
+
+            let result = serde_cbor::to_vec(&Result::<(), _>::Err(serialization_error())).unwrap();
+            assert_eq!(result, SERIALIZATION_ERROR_RESULT);
+        }
+
+        fn metadata(dynamite_version: &str) -> AdapterMetadata {
+            AdapterMetadata {
+                name: "test_adapter".into(),
+                version: "1.0.0".into(),
+                dynamite_version: dynamite_version.into(),
+                min_dynamite_version: None,
+                max_dynamite_version: None,
+            }
+        }
+
+        fn range(min: &str, max: &str) -> Option<(String, String)> {
+            Some((min.into(), max.into()))
+        }
+
+        #[test]
+        fn supported_versions_default_to_the_semver_compatible_range() {
+            assert_eq!(
+                metadata("1.4.2").supported_versions(),
+                range("1.0.0", "2.0.0")
+            );
+            assert_eq!(
+                metadata("0.2.5").supported_versions(),
+                range("0.2.0", "0.3.0")
+            );
+            assert_eq!(
+                metadata("0.0.3").supported_versions(),
+                range("0.0.3", "0.0.4")
+            );
+            assert_eq!(
+                metadata("0.2.5-beta.1+build").supported_versions(),
+                range("0.2.0", "0.3.0")
+            );
+            assert_eq!(metadata("0.2").supported_versions(), None);
+            assert_eq!(metadata("0.2.x").supported_versions(), None);
+        }
+
+        #[test]
+        fn declared_bounds_replace_the_default_range() {
+            let mut metadata = metadata("0.2.5");
+            metadata.min_dynamite_version = Some("0.2.3".into());
+            assert_eq!(metadata.supported_versions(), range("0.2.3", "0.3.0"));
+
+            metadata.max_dynamite_version = Some("0.5.0".into());
+            assert_eq!(metadata.supported_versions(), range("0.2.3", "0.5.0"));
+        }
+
+        #[test]
+        fn adapters_built_with_this_version_are_compatible() {
+            let mut adapter = metadata(VERSION);
+            assert!(adapter.is_compatible());
+
+            // The upper bound is exclusive
+            adapter.max_dynamite_version = Some(VERSION.into());
+            assert!(!adapter.is_compatible());
+
+            adapter.max_dynamite_version = None;
+            adapter.min_dynamite_version = Some("not a version".into());
+            assert!(!adapter.is_compatible());
+
+            assert!(!metadata("not a version").is_compatible());
+        }
+
+        #[test]
+        fn cbor_results_round_trip() {
+            let ok = encode_cbor_result(&Ok::<_, CallError>(vec![1u32, 2, 3]));
+            assert_eq!(decode_cbor_result::<Vec<u32>>(&ok).unwrap(), vec![1, 2, 3]);
+
+            let err = encode_cbor_result::<()>(&Err(CallError::AdapterError("boom".into())));
+            match decode_cbor_result::<()>(&err) {
+                Err(CallError::AdapterError(message)) => assert_eq!(message, "boom"),
+                other => panic!("Unexpected result: {:?}", other),
+            }
+        }
     }
 }
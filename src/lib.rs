@@ -12,7 +12,6 @@
 //! ## Host Application
 //!
 //! ```no_run
-//! use std::collections::HashMap;
 //! use dynamite::*;
 //!
 //! /// A Rust function that we want to create bindings to so that it can be called from other lanuguage
@@ -39,25 +38,28 @@
 //!         api.insert(
 //!             "native::rust_func".into(),
 //!             ScriptType::Function(FunctionDefinition {
-//!                 arguments: HashMap::new(),
+//!                 arguments: vec![],
 //!                 return_type: None,
+//!                 return_ownership: ReturnOwnership::Borrowed,
+//!                 fallible: false,
+//!                 optional_return: false,
 //!             }),
 //!         );
 //!
 //!         api
 //!     }
 //!
-//!     fn call_function(
+//!     unsafe fn call_function(
 //!         &self,
 //!         _host_functions: &dyn HostFunctions,
 //!         path: &str,
 //!         _args: &[*const Void],
-//!     ) -> *const Void {
+//!     ) -> Result<*const Void, CallError> {
 //!         if path == "native::rust_func" {
 //!             rust_func();
-//!             std::ptr::null()
+//!             Ok(std::ptr::null())
 //!         } else {
-//!             panic!("Function not defined");
+//!             Err(CallError::NotFound(path.into()))
 //!         }
 //!     }
 //! }
@@ -66,28 +68,29 @@
 //!     // Initialize dynamite
 //!     let mut dynamite = Dynamite::new();
 //!
-//!     // Add our native Rust adapter
-//!     dynamite.add_language_adapter(Box::new(NativeLanguageAdapter))?;
+//!     // Add our native Rust adapter. All of the paths in its API must be inside of the `native`
+//!     // namespace.
+//!     dynamite.add_language_adapter(Box::new(NativeLanguageAdapter), "native")?;
 //!
 //!     // Load langauge adapter ( relatively safe, but still unsafe because dynamic libraries could do
 //!     // _anything_ 👀 )
 //!     unsafe {
-//!         dynamite.load_dynamic_library_language_adapter("./target/debug/libdynamite_python.so")?
+//!         dynamite.load_dynamic_library_language_adapter(
+//!             "./target/debug/libdynamite_python.so",
+//!             "python",
+//!         )?
 //!     };
 //!
 //!     // Print discovered api
-//!     dbg!(dynamite.get_full_api());
+//!     dbg!(dynamite.get_full_api()?);
 //!
 //!     // Call a function provided by the language adapter ( just assuming for this example that we
-//!     // know ahead of time that this function exists, it would error if it didn't ). This is also
-//!     // unsafe because your language adapter could mis-behave.
-//!     let arg1 = &42f32;
-//!     unsafe {
-//!         dynamite.call_function(
-//!             &"python::test_function".to_string(),
-//!             &[arg1 as *const f32 as *const Void],
-//!         );
-//!     }
+//!     // know ahead of time that this function exists, it would return a `CallError` if it didn't ).
+//!     // The argument types are checked against the function definition before it is called.
+//!     dynamite.call(
+//!         &"python::test_function".into(),
+//!         &ArgBuffer::new().with(42f32),
+//!     )?;
 //!
 //!     Ok(())
 //! }
@@ -98,7 +101,6 @@
 //! _This isn't really a Python language adapter, it's really just Rust, but we'll add Python later 😉_
 //!
 //! ```ignore
-//! use std::collections::HashMap;
 //! use dynamite::*;
 //!
 //! /// The Dynamite Python language adapter
@@ -120,14 +122,11 @@
 //!         components.insert(
 //!             "python::test_function".into(),
 //!             ScriptType::Function(FunctionDefinition {
-//!                 arguments: {
-//!                     let mut h = HashMap::new();
-//!
-//!                     h.insert("number".into(), "std::f32".into());
-//!
-//!                     h
-//!                 },
+//!                 arguments: vec![FunctionArgument::new("number", f32::script_path())],
 //!                 return_type: None,
+//!                 return_ownership: ReturnOwnership::Borrowed,
+//!                 fallible: false,
+//!                 optional_return: false,
 //!             }),
 //!         );
 //!
@@ -135,27 +134,27 @@
 //!     }
 //!
 //!     /// Call functions provided by this adapter
-//!     fn call_function(
+//!     unsafe fn call_function(
 //!         &self,
 //!         host_functions: &dyn HostFunctions,
 //!         path: &str,
 //!         args: &[*const dynamite::Void],
-//!     ) -> *const dynamite::Void {
+//!     ) -> Result<*const dynamite::Void, CallError> {
 //!         if path == "python::test_function" {
 //!             let arg1 = args[0];
 //!
-//!             let number = unsafe { &*(arg1 as *const f32) };
+//!             let number = &*(arg1 as *const f32);
 //!
 //!             println!("The number is: {}", number);
 //!
-//!             dbg!(host_functions.get_full_api());
+//!             dbg!(host_functions.get_full_api()?);
 //!
-//!             unsafe {
-//!                 host_functions.call_function(&"native::rust_func".to_string(), &[]);
-//!             }
-//!         }
+//!             host_functions.call_function(&"native::rust_func".into(), &[])?;
 //!
-//!         std::ptr::null()
+//!             Ok(std::ptr::null())
+//!         } else {
+//!             Err(CallError::NotFound(path.into()))
+//!         }
 //!     }
 //! }
 //! ```
@@ -165,7 +164,13 @@
 #[macro_use]
 extern crate dlopen_derive;
 
-use std::{collections::HashMap, ffi::OsStr};
+use std::{
+    cell::RefCell,
+    ffi::OsStr,
+    ops::Deref,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 // Language adapter traits and types
 mod language_adapter;
@@ -175,6 +180,22 @@ pub use language_adapter::*;
 mod script_api;
 pub use script_api::*;
 
+// Ref-counted handles and vectors that can be shared between adapters
+mod allocation;
+pub use allocation::*;
+
+// Callbacks that can be passed between adapters
+mod callback;
+pub use callback::*;
+
+// Interned type paths and resolved function handles
+mod symbol;
+pub use symbol::*;
+
+// Typed function arguments and return values
+mod value;
+pub use value::*;
+
 // Dynamite stockpile types and implementations
 mod stockpile;
 pub use crate::stockpile::*;
@@ -188,19 +209,113 @@ pub mod _macros_private {
     pub use once_cell;
     pub use serde_cbor;
     pub use inventory;
+    pub use safer_ffi;
 }
 
 /// The main struct used to create a Dynamite host and load language adapters
 #[derive(Default)]
 pub struct Dynamite {
-    /// The set of language adapters
-    adapters: Vec<Box<dyn LanguageAdapter>>,
+    /// The set of language adapters, indexed by [`AdapterId`]. Removed adapters leave an empty
+    /// slot so that the ids of the other adapters stay the same.
+    adapters: Vec<Option<AdapterEntry>>,
+
+    /// The interned paths of every type in the scripting API
+    symbols: SymbolTable,
+
+    /// The type registered under each [`SymbolId`] and the index of the adapter that provides it,
+    /// or `None` if the adapter has been removed
+    symbol_entries: Vec<Option<ApiEntry>>,
+
+    /// Whether or not to validate every function call against its [`FunctionDefinition`]
+    checked_calls: bool,
+
+    /// The callbacks registered by language adapters. The host is only used from one thread, but
+    /// callbacks are registered and released through `&self` while adapters are called.
+    callbacks: RefCell<CallbackTable>,
+
+    /// The next adapter instance or generation number
+    next_generation: u32,
+}
+
+/// A language adapter added to the host
+struct AdapterEntry {
+    adapter: Box<dyn LanguageAdapter>,
+    /// The namespace root of the adapter, or `None` if the adapter may register any path
+    namespace: Option<TypePath>,
+    /// A number that is unique to this instance of the adapter, used to find the callbacks that it
+    /// has registered
+    instance: u32,
+    /// A number that is unique to this instance of the adapter, so that [`FunctionHandle`]s to an
+    /// adapter that has been reloaded under the same [`AdapterId`] can be told apart
+    generation: u32,
+    /// The library that the adapter was loaded from, if it was loaded from a dynamic library
+    library: Option<AdapterLibrary>,
+}
+
+/// The dynamic library that an adapter was loaded from
+struct AdapterLibrary {
+    path: PathBuf,
+    /// The modification time of the library when it was loaded
+    modified: Option<SystemTime>,
+}
+
+impl AdapterLibrary {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_owned(),
+            modified: Self::modified_time(path),
+        }
+    }
+
+    fn modified_time(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Whether the library on disk has been modified since it was loaded
+    fn is_modified(&self) -> bool {
+        let modified = Self::modified_time(&self.path);
+        modified.is_some() && modified != self.modified
+    }
+}
+
+/// An adapter borrowed to make a call into it
+///
+/// The adapters are only ever modified through `&mut Dynamite`, so the borrow checker makes sure
+/// that an adapter can't be removed or reloaded while a call into it is in flight.
+struct AdapterCall<'a> {
+    /// The index of the adapter in [`Dynamite::adapters`]
+    index: usize,
+    entry: &'a AdapterEntry,
+    /// The host functions given to the adapter
+    host: AdapterHost<'a>,
+}
 
-    /// A cache of the APIs provided by loaded adapters
-    api_cache: Vec<ScriptApi>,
+impl<'a> AdapterCall<'a> {
+    fn new(dynamite: &'a Dynamite, index: usize, entry: &'a AdapterEntry) -> Self {
+        Self {
+            index,
+            entry,
+            host: dynamite.adapter_host(entry.instance),
+        }
+    }
+}
+
+impl<'a> Deref for AdapterCall<'a> {
+    type Target = dyn LanguageAdapter;
 
-    /// Mapping of [`TypePath`]s to the adapter/api_cache index that provides that type
-    type_adapter_index: HashMap<TypePath, usize>,
+    fn deref(&self) -> &Self::Target {
+        self.entry.adapter.as_ref()
+    }
+}
+
+/// A type in the scripting API and the adapter that provides it
+struct ApiEntry {
+    /// The index of the adapter in [`Dynamite::adapters`]
+    adapter: usize,
+    /// The type's definition
+    script_type: ScriptType,
 }
 
 impl Dynamite {
@@ -212,95 +327,1044 @@ impl Dynamite {
     /// Add the Dynamite stockpile to the scripting api
     ///
     /// The Dynamite stockpile allows you to automatically add all stockpile-integrated API bindings
-    /// from your crate and all other linked crates.
-    pub fn add_stockpile(&mut self) -> Result<(), ScriptApiError> {
-        self.add_language_adapter(Box::new(Stockpile::new()?))
+    /// from your crate and all other linked crates. Because those bindings live under the module
+    /// paths of the crates that define them, the stockpile is not restricted to a namespace.
+    pub fn add_stockpile(&mut self) -> Result<AdapterId, ScriptApiError> {
+        let id = AdapterId(self.adapters.len());
+        let instance = self.new_generation();
+        self.insert_language_adapter(id, instance, Box::new(Stockpile::new()?), None)
     }
 
     /// Load a language adapter from a dynamically linked library
     ///
     /// This allows you to load language adapters from .dll ( Windows ), .so ( Linux ), or .dylib (
     /// Mac ) files.
-    pub unsafe fn load_dynamic_library_language_adapter<P: AsRef<OsStr>>(
+    ///
+    /// # Safety
+    ///
+    /// Loading a dynamic library may run arbitrary code, and the library must implement the
+    /// [`LanguageAdapterCApi`] with the signatures expected by this version of Dynamite.
+    pub unsafe fn load_dynamic_library_language_adapter<P: AsRef<OsStr>, N: Into<TypePath>>(
         &mut self,
         path: P,
-    ) -> Result<(), DynamiteError> {
-        // Create the C function pointers used to call dynamite functions from the dynamic library
-        let pointers = CHostFunctionPointers {
-            get_full_api: ffi::dynamite_get_full_api,
-            call_function: ffi::dynamite_call_function,
-        };
+        namespace: N,
+    ) -> Result<AdapterId, DynamiteError> {
+        let namespace = namespace.into();
+        self.validate_namespace(&namespace)?;
+        let path = Path::new(path.as_ref());
+        let library = AdapterLibrary::new(path);
 
         // Add the language adapter
-        self.add_language_adapter(Box::new(LoadedDynamicLibLanguageAdapter::load(
-            path, pointers,
-        )?))?;
+        let id = AdapterId(self.adapters.len());
+        let instance = self.new_generation();
+        let adapter = self.load_library_instance(path, instance, false)?;
+        self.insert_language_adapter(id, instance, Box::new(adapter), Some(namespace))?;
+        self.set_adapter_library(id, library);
 
-        Ok(())
+        Ok(id)
+    }
+
+    /// Load an instance of a library adapter, which owns the callbacks that it registers while it
+    /// is loaded. The library is loaded from a copy if `copy` is set.
+    unsafe fn load_library_instance(
+        &self,
+        path: &Path,
+        instance: u32,
+        copy: bool,
+    ) -> Result<LoadedDynamicLibLanguageAdapter, DynamiteError> {
+        let pointers = Self::host_function_pointers();
+        let adapter = if copy {
+            LoadedDynamicLibLanguageAdapter::load_copy(path, pointers, self, instance)
+        } else {
+            LoadedDynamicLibLanguageAdapter::load(path, pointers, self, instance)
+        };
+
+        // The library has already been unloaded if loading it failed, so the callbacks that it
+        // registered can only be revoked, not released
+        if adapter.is_err() {
+            self.callbacks.borrow_mut().revoke_owner(instance);
+        }
+
+        adapter
+    }
+
+    /// Reload a language adapter that was loaded from a dynamic library
+    ///
+    /// The library is loaded again from a copy with [`LoadedDynamicLibLanguageAdapter::load_copy`]
+    /// so that the library on disk is loaded even if the old one could not be unloaded. Once the
+    /// new instance of the adapter has been loaded and its API has been validated, the old instance
+    /// is removed as with [`Dynamite::remove_language_adapter`] and the new one takes its place,
+    /// keeping its [`AdapterId`] and namespace. Returns the difference between the old and the new
+    /// API of the adapter.
+    ///
+    /// [`FunctionHandle`]s to the old instance of the adapter return [`CallError::InvalidHandle`]
+    /// and must be resolved again, while [`SymbolId`]s stay the same.
+    ///
+    /// If the new library can't be loaded, such as when it is only partly written or its API is
+    /// invalid, the old instance is kept and [`Dynamite::reload_modified_adapters`] only tries
+    /// again once the library is modified again. If shutting down the old instance fails, it is
+    /// still replaced and the error is returned.
+    ///
+    /// # Safety
+    ///
+    /// See [`Dynamite::load_dynamic_library_language_adapter`].
+    pub unsafe fn reload_adapter(&mut self, id: AdapterId) -> Result<ApiDiff, DynamiteError> {
+        let entry = self.adapter_entry(id)?;
+        let path = entry
+            .library
+            .as_ref()
+            .ok_or(DynamiteError::NotReloadable(id))?
+            .path
+            .clone();
+        let namespace = entry.namespace.clone();
+        let state = entry
+            .adapter
+            .serialize_state(&self.adapter_host(entry.instance))?;
+
+        // Load the new instance next to the old one, so that the old one can be kept if loading
+        // fails. The modification time is checked before the library is copied, so that changes
+        // made while it is being loaded cause another reload.
+        let library = AdapterLibrary::new(&path);
+        let instance = self.new_generation();
+        let loaded = self
+            .load_library_instance(&path, instance, true)
+            .and_then(|adapter| {
+                let api = self.load_adapter_api(id, instance, &adapter, namespace.as_ref())?;
+                Ok((adapter, api))
+            });
+        let (adapter, api) = match loaded {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                // Don't try to load the same broken library again
+                self.set_adapter_library(id, library);
+                return Err(error);
+            }
+        };
+
+        // Replace the old instance with the new one
+        let old_api = self.get_adapter_api(id);
+        let shutdown = self.shutdown_language_adapter(self.adapter_entry(id)?);
+        self.remove_adapter_api(id);
+        let old_entry = self.adapters[id.0].take();
+        let entry = AdapterEntry {
+            adapter: Box::new(adapter),
+            namespace,
+            instance,
+            generation: instance,
+            library: Some(library),
+        };
+        self.install_language_adapter(id, entry, api);
+        drop(old_entry);
+
+        // Give the state of the old instance to the new one
+        self.adapter_entry(id)?
+            .adapter
+            .restore_state(&self.adapter_host(instance), &state)?;
+        shutdown?;
+
+        Ok(ApiDiff::new(&old_api, &self.get_adapter_api(id)))
+    }
+
+    /// Reload the scripts of a language adapter without reloading the adapter itself
+    ///
+    /// The adapter's state is saved with [`LanguageAdapter::serialize_state`] before its scripts
+    /// are reloaded with [`LanguageAdapter::reload_scripts`], and is given back to it with
+    /// [`LanguageAdapter::restore_state`] afterwards. The adapter's new API then replaces its old
+    /// API in the host, and the difference between the two is returned.
+    ///
+    /// [`FunctionHandle`]s to the adapter's functions become invalid as soon as its scripts have
+    /// been reloaded, because the adapter may have moved its functions to different slots.
+    ///
+    /// If reloading the scripts fails, nothing changes. If the new API is invalid, the adapter's
+    /// API is removed from the host, because the adapter no longer provides the old one, and the
+    /// error is returned. Reloading the adapter's scripts again once they have been fixed adds its
+    /// API back. If restoring the state fails, the new API still replaces the old one and the error
+    /// is returned.
+    pub fn reload_scripts(&mut self, id: AdapterId) -> Result<ApiDiff, DynamiteError> {
+        let entry = self.adapter_entry(id)?;
+        let namespace = entry.namespace.clone();
+
+        let (api, restored) = {
+            let host = self.adapter_host(entry.instance);
+            let state = entry.adapter.serialize_state(&host)?;
+            entry.adapter.reload_scripts(&host)?;
+            let restored = entry.adapter.restore_state(&host, &state);
+
+            (entry.adapter.get_api(&host), restored)
+        };
+
+        // Invalidate the handles to the adapter's functions
+        let generation = self.new_generation();
+        if let Some(entry) = self.adapters[id.0].as_mut() {
+            entry.generation = generation;
+        }
+
+        // Replace the adapter's API, leaving it without an API if the new one is invalid
+        let old_api = self.get_adapter_api(id);
+        self.remove_adapter_api(id);
+        self.validate_adapter_api(id, &api, namespace.as_ref())?;
+        self.index_adapter_api(id, api);
+        restored?;
+
+        Ok(ApiDiff::new(&old_api, &self.get_adapter_api(id)))
+    }
+
+    /// Reload every adapter whose dynamic library has been modified since it was loaded
+    ///
+    /// This can be called regularly, such as once per frame, to pick up adapters as soon as they are
+    /// rebuilt. Every modified adapter is reloaded even if reloading another one fails, and the
+    /// result of reloading each of them is returned along with its id.
+    ///
+    /// # Safety
+    ///
+    /// See [`Dynamite::load_dynamic_library_language_adapter`].
+    pub unsafe fn reload_modified_adapters(
+        &mut self,
+    ) -> Vec<(AdapterId, Result<ApiDiff, DynamiteError>)> {
+        let modified = self
+            .adapters
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                let library = entry.as_ref().and_then(|entry| entry.library.as_ref());
+                library.map(AdapterLibrary::is_modified).unwrap_or(false)
+            })
+            .map(|(index, _)| AdapterId(index))
+            .collect::<Vec<_>>();
+
+        modified
+            .into_iter()
+            .map(|id| (id, self.reload_adapter(id)))
+            .collect()
+    }
+
+    /// Record the library that an adapter was loaded from
+    fn set_adapter_library(&mut self, id: AdapterId, library: AdapterLibrary) {
+        if let Some(entry) = self.adapters.get_mut(id.0).and_then(Option::as_mut) {
+            entry.library = Some(library);
+        }
+    }
+
+    /// Create the C function pointers used to call dynamite functions from dynamic libraries
+    fn host_function_pointers() -> CHostFunctionPointers {
+        CHostFunctionPointers {
+            get_full_api: ffi::dynamite_get_full_api,
+            call_function: ffi::dynamite_call_function,
+            resolve_symbol: ffi::dynamite_resolve_symbol,
+            resolve_function: ffi::dynamite_resolve_function,
+            call_handle: ffi::dynamite_call_handle,
+            free_value: ffi::dynamite_free_value,
+            get_global: ffi::dynamite_get_global,
+            set_global: ffi::dynamite_set_global,
+            register_callback: ffi::dynamite_register_callback,
+            retain_callback: ffi::dynamite_retain_callback,
+            release_callback: ffi::dynamite_release_callback,
+            call_callback: ffi::dynamite_call_callback,
+            free_callback_value: ffi::dynamite_free_callback_value,
+        }
     }
 
     /// Add a language adapter from any type implementing [`LanguageAdapter`]
     ///
-    /// This can be used to easily add native Rust bindings to the scripting API.
-    pub fn add_language_adapter(
+    /// This can be used to easily add native Rust bindings to the scripting API. Every path in the
+    /// adapter's API must be inside of the `namespace` root, such as `python` for
+    /// `python::test_function`. The namespace may not overlap with the namespace of another
+    /// adapter or with any path that is already in the scripting API, such as the module paths
+    /// used by the stockpile.
+    pub fn add_language_adapter<N: Into<TypePath>>(
+        &mut self,
+        adapter: Box<dyn LanguageAdapter>,
+        namespace: N,
+    ) -> Result<AdapterId, ScriptApiError> {
+        let namespace = namespace.into();
+        self.validate_namespace(&namespace)?;
+
+        let id = AdapterId(self.adapters.len());
+        let instance = self.new_generation();
+        self.insert_language_adapter(id, instance, adapter, Some(namespace))
+    }
+
+    /// Make sure that a namespace is valid and doesn't overlap with the namespace or the API of
+    /// another adapter
+    fn validate_namespace(&self, namespace: &TypePath) -> Result<(), ScriptApiError> {
+        namespace.validate()?;
+
+        // Paths in the API are checked as well, because adapters without a namespace, such as the
+        // stockpile, may have added paths anywhere
+        let namespaces = self.adapters.iter().flatten();
+        let namespaces = namespaces.filter_map(|entry| entry.namespace.as_ref());
+        let paths = self
+            .symbols
+            .paths()
+            .zip(&self.symbol_entries)
+            .filter(|(_, entry)| entry.is_some())
+            .map(|(path, _)| path);
+        for other in namespaces.chain(paths) {
+            if namespace.is_within(other) || other.is_within(namespace) {
+                return Err(ScriptApiError::NamespaceTaken(namespace.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a new number for an adapter instance or generation
+    fn new_generation(&mut self) -> u32 {
+        let generation = self.next_generation;
+        self.next_generation += 1;
+
+        generation
+    }
+
+    /// Get the host functions given to an adapter instance, which owns the callbacks registered
+    /// through them
+    fn adapter_host(&self, instance: u32) -> AdapterHost<'_> {
+        AdapterHost {
+            dynamite: self,
+            instance,
+        }
+    }
+
+    /// Add an instance of a language adapter under the given id, which must be an empty slot or
+    /// the next id, restricting its API to the given namespace if there is one
+    fn insert_language_adapter(
         &mut self,
+        id: AdapterId,
+        instance: u32,
         adapter: Box<dyn LanguageAdapter>,
+        namespace: Option<TypePath>,
+    ) -> Result<AdapterId, ScriptApiError> {
+        let api = self.load_adapter_api(id, instance, adapter.as_ref(), namespace.as_ref())?;
+        let entry = AdapterEntry {
+            adapter,
+            namespace,
+            instance,
+            generation: instance,
+            library: None,
+        };
+        self.install_language_adapter(id, entry, api);
+
+        Ok(id)
+    }
+
+    /// Get the API of a new adapter instance and make sure that it can be added under the given id
+    ///
+    /// The callbacks registered by the instance are released if its API can't be added.
+    fn load_adapter_api(
+        &self,
+        id: AdapterId,
+        instance: u32,
+        adapter: &dyn LanguageAdapter,
+        namespace: Option<&TypePath>,
+    ) -> Result<ScriptApi, ScriptApiError> {
+        let api = adapter.get_api(&self.adapter_host(instance));
+        if let Err(error) = self.validate_adapter_api(id, &api, namespace) {
+            // Release the adapter's callbacks while it is still loaded
+            self.release_instance_callbacks(instance);
+            return Err(error);
+        }
+
+        Ok(api)
+    }
+
+    /// Put an adapter in the slot of the given id, which must be empty or the next id, and add its
+    /// API to the index
+    fn install_language_adapter(&mut self, id: AdapterId, entry: AdapterEntry, api: ScriptApi) {
+        self.index_adapter_api(id, api);
+
+        if id.0 < self.adapters.len() {
+            self.adapters[id.0] = Some(entry);
+        } else {
+            self.adapters.push(Some(entry));
+        }
+    }
+
+    /// Make sure that the API of the adapter with the given id can be added to the host
+    fn validate_adapter_api(
+        &self,
+        id: AdapterId,
+        api: &ScriptApi,
+        namespace: Option<&TypePath>,
     ) -> Result<(), ScriptApiError> {
-        // Load the adapter API
-        let api = adapter.get_api(self);
+        // Make sure the paths are valid and inside of the adapter's namespace
+        for path in api.keys() {
+            path.validate()?;
+
+            if let Some(namespace) = namespace {
+                if !path.is_within(namespace) {
+                    return Err(ScriptApiError::OutsideNamespace {
+                        path: path.clone(),
+                        namespace: namespace.clone(),
+                    });
+                }
+            }
+        }
 
-        // Check for conflicting types
+        // Make sure the paths are not inside of the namespace of another adapter
+        let other_namespaces = self
+            .adapters
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| index != id.0)
+            .filter_map(|(_, entry)| entry.as_ref()?.namespace.as_ref())
+            .collect::<Vec<_>>();
         for path in api.keys() {
-            if self.type_adapter_index.contains_key(path) {
-                return Err(ScriptApiError::TypeRedefined(path.clone()));
+            if let Some(&other) = other_namespaces.iter().find(|other| path.is_within(other)) {
+                return Err(ScriptApiError::InsideNamespace {
+                    path: path.clone(),
+                    namespace: other.clone(),
+                });
             }
         }
 
-        // Add types to the adapter type_adapter_index
+        // Check for types defined by other adapters
         for path in api.keys() {
-            // Map the type path to the the index of this adapter
-            self.type_adapter_index
-                .insert(path.clone(), self.api_cache.len());
+            let defined_elsewhere = self
+                .symbols
+                .get(path)
+                .and_then(|symbol| self.symbol_entries[symbol.index()].as_ref())
+                .filter(|entry| entry.adapter != id.0);
+            if defined_elsewhere.is_some() {
+                return Err(ScriptApiError::TypeRedefined(path.clone()));
+            }
+        }
+
+        // Make sure struct and enum layouts are valid so that adapters can safely access their
+        // fields
+        for (path, script_type) in api {
+            let result = match script_type {
+                ScriptType::Struct(definition) => definition.validate_layout(),
+                ScriptType::Enum(definition) => definition.validate_layout(),
+                _ => Ok(()),
+            };
+            result.map_err(|error| ScriptApiError::InvalidLayout {
+                path: path.clone(),
+                error,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the API of the adapter with the given id to the index
+    fn index_adapter_api(&mut self, id: AdapterId, api: ScriptApi) {
+        // Intern the paths in a stable order and add the types to the index. Paths that belonged
+        // to a removed adapter keep their old symbol.
+        let mut types = api.into_iter().collect::<Vec<_>>();
+        types.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (path, script_type) in types {
+            let symbol = self.symbols.intern(&path);
+            if symbol.index() >= self.symbol_entries.len() {
+                self.symbol_entries.resize_with(symbol.index() + 1, || None);
+            }
+            self.symbol_entries[symbol.index()] = Some(ApiEntry {
+                adapter: id.0,
+                script_type,
+            });
+        }
+    }
+
+    /// Remove the API of the adapter with the given id from the index
+    fn remove_adapter_api(&mut self, id: AdapterId) {
+        for entry in &mut self.symbol_entries {
+            if entry.as_ref().map(|entry| entry.adapter) == Some(id.0) {
+                *entry = None;
+            }
+        }
+    }
+
+    /// Get an adapter that has been added to the host
+    fn adapter_entry(&self, id: AdapterId) -> Result<&AdapterEntry, DynamiteError> {
+        self.adapters
+            .get(id.0)
+            .and_then(Option::as_ref)
+            .ok_or(DynamiteError::AdapterNotFound(id))
+    }
+
+    /// Remove a language adapter from the host
+    ///
+    /// The adapter's paths are removed from the scripting API, and the adapter is shut down with
+    /// [`LanguageAdapter::shutdown`] before it is dropped, which unloads adapters loaded from
+    /// dynamic libraries. [`FunctionHandle`]s to the adapter's functions become invalid, but the
+    /// [`SymbolId`]s of its paths are kept so that they refer to the same paths if those are added
+    /// again by another adapter.
+    ///
+    /// The callbacks registered by the adapter are released before it is dropped, and handles to
+    /// them that are still held by other adapters return [`CallError::InvalidCallbackHandle`].
+    ///
+    /// Removing an adapter takes `&mut self`, so no calls into the adapter can be in flight while
+    /// it is removed. The adapter is removed even if shutting it down returns an error.
+    pub fn remove_language_adapter(&mut self, id: AdapterId) -> Result<(), DynamiteError> {
+        self.adapter_entry(id)?;
+        self.remove_adapter_api(id);
+
+        // Shut the adapter down while it can still call into the host
+        let entry = self.adapters[id.0]
+            .take()
+            .expect("Internal error removing adapter");
+
+        Ok(self.shutdown_language_adapter(&entry)?)
+    }
+
+    /// Shut an adapter instance down and release its callbacks before its library is unloaded
+    fn shutdown_language_adapter(&self, entry: &AdapterEntry) -> Result<(), CallError> {
+        let result = entry.adapter.shutdown(&self.adapter_host(entry.instance));
+        self.release_instance_callbacks(entry.instance);
+
+        result
+    }
+
+    /// Release the callbacks registered by an adapter instance, so that the handles to them that
+    /// are held by other adapters become invalid
+    fn release_instance_callbacks(&self, instance: u32) {
+        let callbacks = self.callbacks.borrow_mut().revoke_owner(instance);
+        for callback in callbacks {
+            unsafe { (callback.release)(callback.user_data) };
+        }
+    }
+
+    /// Enable or disable checked calls
+    ///
+    /// When checked calls are enabled, every call made through [`HostFunctions::call_function`],
+    /// including calls made by language adapters, is validated with [`Dynamite::validate_call`]
+    /// before it is dispatched to the adapter that provides the function.
+    pub fn set_checked_calls(&mut self, checked_calls: bool) {
+        self.checked_calls = checked_calls;
+    }
+
+    /// Get the path of a symbol
+    pub fn symbol_path(&self, symbol: SymbolId) -> Option<&TypePath> {
+        self.symbols.path(symbol)
+    }
+
+    /// Get the [`ScriptType`] registered under a symbol
+    pub fn get_script_type(&self, symbol: SymbolId) -> Option<&ScriptType> {
+        self.symbol_entries
+            .get(symbol.index())?
+            .as_ref()
+            .map(|entry| &entry.script_type)
+    }
+
+    /// Get the part of the scripting API that is provided by an adapter
+    pub fn get_adapter_api(&self, id: AdapterId) -> ScriptApi {
+        self.symbols
+            .paths()
+            .zip(&self.symbol_entries)
+            .filter_map(|(path, entry)| {
+                let entry = entry.as_ref().filter(|entry| entry.adapter == id.0)?;
+                Some((path.clone(), entry.script_type.clone()))
+            })
+            .collect()
+    }
+
+    /// Get the [`FunctionDefinition`] of the function at the given path
+    pub fn get_function_definition(&self, path: &str) -> Option<&FunctionDefinition> {
+        self.get_symbol_function_definition(self.symbols.get(path)?)
+    }
+
+    /// Get the [`FunctionDefinition`] of the function registered under a symbol
+    fn get_symbol_function_definition(&self, symbol: SymbolId) -> Option<&FunctionDefinition> {
+        match self.get_script_type(symbol)? {
+            ScriptType::Function(definition) => Some(definition),
+            _ => None,
+        }
+    }
+
+    /// Get the value of the [`ScriptType::Constant`] at the given path
+    pub fn get_constant(&self, path: &str) -> Option<&LiteralValue> {
+        match self.get_script_type(self.symbols.get(path)?)? {
+            ScriptType::Constant { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the type and settability of the [`ScriptType::Global`] registered under a symbol
+    fn get_global_definition(&self, symbol: SymbolId) -> Result<(&TypePath, bool), CallError> {
+        let path = self
+            .symbols
+            .path(symbol)
+            .ok_or(CallError::SymbolNotFound(symbol.id))?;
+        match self.get_script_type(symbol) {
+            Some(ScriptType::Global {
+                type_path,
+                settable,
+            }) => Ok((type_path, *settable)),
+            Some(_) => Err(CallError::NotAGlobal(path.clone())),
+            None => Err(CallError::NotFound(path.clone())),
+        }
+    }
+
+    /// Get a copy of the value of a global
+    ///
+    /// Returns an error if the global is not of type `T`.
+    pub fn read_global<T: HasScriptType>(&self, path: &TypePath) -> Result<T, CallError> {
+        let symbol = self.resolve_symbol(path)?;
+        let (type_path, _) = self.get_global_definition(symbol)?;
+        if type_path != &T::script_path() {
+            return Err(CallError::GlobalTypeMismatch {
+                path: path.clone(),
+                declared: type_path.clone(),
+                requested: T::script_path(),
+            });
+        }
+
+        let mut value = std::mem::MaybeUninit::<T>::uninit();
+        unsafe {
+            self.get_global(symbol, value.as_mut_ptr() as *mut Void)?;
+            Ok(value.assume_init())
+        }
+    }
+
+    /// Set the value of a global
+    ///
+    /// Returns an error if the global is not of type `T` or is not settable.
+    pub fn write_global<T: HasScriptType>(
+        &self,
+        path: &TypePath,
+        value: T,
+    ) -> Result<(), CallError> {
+        let symbol = self.resolve_symbol(path)?;
+        let (type_path, _) = self.get_global_definition(symbol)?;
+        if type_path != &T::script_path() {
+            return Err(CallError::GlobalTypeMismatch {
+                path: path.clone(),
+                declared: type_path.clone(),
+                requested: T::script_path(),
+            });
+        }
+
+        // The adapter copies the value, so we still drop our own copy afterwards
+        unsafe { self.set_global(symbol, &value as *const T as *const Void) }
+    }
+
+    /// Validate a function call against the function's [`FunctionDefinition`] without calling it
+    ///
+    /// This makes sure that the function exists and takes `arg_count` arguments. If `arg_types` is
+    /// provided, it also makes sure that the type path of each argument matches the type of the
+    /// argument declared in the same position.
+    pub fn validate_call(
+        &self,
+        path: &TypePath,
+        arg_count: usize,
+        arg_types: Option<&[TypePath]>,
+    ) -> Result<(), CallError> {
+        self.validate_symbol_call(self.resolve_symbol(path)?, arg_count, arg_types)
+    }
+
+    /// Call the function registered under a symbol, validating the call first if checked calls are
+    /// enabled
+    unsafe fn call_symbol(
+        &self,
+        symbol: SymbolId,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        if self.checked_calls {
+            self.validate_symbol_call(symbol, args.len(), None)?;
+        }
+
+        self.dispatch_call(symbol, args)
+    }
+
+    /// Validate a call to the function registered under a symbol, as described in
+    /// [`Dynamite::validate_call`]
+    fn validate_symbol_call(
+        &self,
+        symbol: SymbolId,
+        arg_count: usize,
+        arg_types: Option<&[TypePath]>,
+    ) -> Result<(), CallError> {
+        let (path, _) = self.symbol_adapter(symbol)?;
+        let definition = self
+            .get_symbol_function_definition(symbol)
+            .ok_or_else(|| CallError::NotFound(path.clone()))?;
+
+        // Check the number of arguments
+        if definition.arguments.len() != arg_count {
+            return Err(CallError::WrongArity {
+                path: path.clone(),
+                expected: definition.arguments.len(),
+                found: arg_count,
+            });
         }
 
-        // Add the types to the cache
-        self.api_cache.push(api);
+        // Check the argument types
+        if let Some(arg_types) = arg_types {
+            if arg_types.len() != arg_count {
+                return Err(CallError::WrongArity {
+                    path: path.clone(),
+                    expected: definition.arguments.len(),
+                    found: arg_types.len(),
+                });
+            }
 
-        // Add the adapter to the list
-        self.adapters.push(adapter);
+            for (index, (argument, arg_type)) in
+                definition.arguments.iter().zip(arg_types).enumerate()
+            {
+                if &argument.type_path != arg_type {
+                    return Err(CallError::TypeMismatch {
+                        path: path.clone(),
+                        index,
+                        expected: argument.type_path.clone(),
+                        found: arg_type.clone(),
+                    });
+                }
+            }
+        }
 
         Ok(())
     }
+
+    /// Call a function provided by the scripting API, validating the call first
+    ///
+    /// This is the same as [`HostFunctions::call_function`], but the call is always checked with
+    /// [`Dynamite::validate_call`] before it is dispatched, even if checked calls are disabled.
+    ///
+    /// # Safety
+    ///
+    /// The `args` must point to valid values of the types expected by the function. Providing
+    /// `arg_types` makes it possible to catch type mistakes, but Dynamite cannot verify that the
+    /// pointers actually point to values of those types.
+    pub unsafe fn call_function_checked(
+        &self,
+        path: &TypePath,
+        args: &[*const Void],
+        arg_types: Option<&[TypePath]>,
+    ) -> Result<*const Void, CallError> {
+        let symbol = self.resolve_symbol(path)?;
+        self.validate_symbol_call(symbol, args.len(), arg_types)?;
+
+        self.dispatch_call(symbol, args)
+    }
+
+    /// Call a function provided by the scripting API with typed arguments
+    ///
+    /// The argument types are checked against the function's [`FunctionDefinition`] before the call
+    /// is dispatched, and the returned [`ReturnValue`] checks the type of the returned value before
+    /// giving access to it. The [`ReturnValue`] frees the returned value when it is dropped if the
+    /// function returns an [`OwnedByCaller`][ReturnOwnership::OwnedByCaller] value.
+    ///
+    /// A [`Borrowed`][ReturnOwnership::Borrowed] return value may point into one of the arguments,
+    /// so the [`ReturnValue`] borrows `args` as well as the host.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use dynamite::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dynamite = Dynamite::new();
+    /// let args = ArgBuffer::new().with(32i32).with(44i32);
+    /// let sum: i32 = dynamite.call(&"hello_world::rust_func".into(), &args)?.get()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The arguments can't be dropped while the returned value is still in use:
+    ///
+    /// ```compile_fail
+    /// # use dynamite::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dynamite = Dynamite::new();
+    /// let args = ArgBuffer::new().with(1f32);
+    /// let value = dynamite.call(&"game::identity".into(), &args)?;
+    /// drop(args);
+    /// let value: f32 = value.get()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call<'a>(
+        &'a self,
+        path: &TypePath,
+        args: &'a ArgBuffer,
+    ) -> Result<ReturnValue<'a>, CallError> {
+        let symbol = self.resolve_symbol(path)?;
+        self.validate_symbol_call(symbol, args.len(), Some(args.type_paths()))?;
+
+        let definition = self
+            .get_symbol_function_definition(symbol)
+            .ok_or_else(|| CallError::NotFound(path.clone()))?;
+        let return_type = definition.return_type.clone();
+        let ownership = definition.return_ownership;
+
+        // Safety: the argument types were checked against the function definition above
+        let ptr = unsafe { self.dispatch_call(symbol, args.pointers())? };
+
+        Ok(ReturnValue::new(
+            self,
+            symbol,
+            path.clone(),
+            ptr,
+            return_type,
+            ownership,
+        ))
+    }
+
+    /// Send a function call to the adapter that provides the function
+    unsafe fn dispatch_call(
+        &self,
+        symbol: SymbolId,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        let (path, adapter) = self.symbol_adapter(symbol)?;
+
+        adapter.call_function(&adapter.host, path, args)
+    }
+
+    /// Run a function with a registered callback, holding a reference to the callback so that it
+    /// can't be released while the function runs, such as by the callback itself
+    fn with_callback<R>(
+        &self,
+        handle: CallbackHandle,
+        f: impl FnOnce(CCallback) -> R,
+    ) -> Result<R, CallError> {
+        let RegisteredCallback { callback, .. } = self.callbacks.borrow_mut().acquire(handle)?;
+        let result = f(callback);
+        self.release_callback(handle)?;
+
+        Ok(result)
+    }
+
+    /// Get the path of a symbol and the adapter that provides it
+    fn symbol_adapter(&self, symbol: SymbolId) -> Result<(&TypePath, AdapterCall<'_>), CallError> {
+        let path = self
+            .symbols
+            .path(symbol)
+            .ok_or(CallError::SymbolNotFound(symbol.id))?;
+        let index = self
+            .symbol_entries
+            .get(symbol.index())
+            .and_then(Option::as_ref)
+            .ok_or_else(|| CallError::NotFound(path.clone()))?
+            .adapter;
+        let entry = self
+            .adapters
+            .get(index)
+            .and_then(Option::as_ref)
+            .expect("Internal error finding adapter");
+
+        Ok((path, AdapterCall::new(self, index, entry)))
+    }
 }
 
 impl HostFunctions for Dynamite {
-    fn get_full_api(&self) -> ScriptApi {
-        let mut full_api = ScriptApi::new();
+    fn get_full_api(&self) -> Result<ScriptApi, CallError> {
+        Ok(self
+            .symbols
+            .paths()
+            .zip(&self.symbol_entries)
+            .filter_map(|(path, entry)| Some((path.clone(), entry.as_ref()?.script_type.clone())))
+            .collect())
+    }
+
+    fn as_dynamite(&self) -> &Dynamite {
+        self
+    }
+
+    unsafe fn call_function(
+        &self,
+        path: &TypePath,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        self.call_symbol(self.resolve_symbol(path)?, args)
+    }
+
+    fn resolve_symbol(&self, path: &str) -> Result<SymbolId, CallError> {
+        self.symbols
+            .get(path)
+            .ok_or_else(|| CallError::NotFound(path.into()))
+    }
+
+    fn resolve_function(&self, path: &str) -> Result<FunctionHandle, CallError> {
+        let symbol = self.resolve_symbol(path)?;
+        let (path, adapter) = self.symbol_adapter(symbol)?;
+        if self.get_symbol_function_definition(symbol).is_none() {
+            return Err(CallError::NotAFunction(path.clone()));
+        }
+
+        let slot = adapter.resolve_function(&adapter.host, path)?;
+
+        Ok(FunctionHandle::new(
+            symbol,
+            adapter.index,
+            adapter.entry.generation,
+            slot,
+        ))
+    }
+
+    unsafe fn call_handle(
+        &self,
+        handle: FunctionHandle,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        let symbol = handle.symbol();
+        let (path, adapter) = self.symbol_adapter(symbol)?;
+
+        // Make sure the handle was created for the adapter that still provides the function
+        if adapter.index != handle.adapter() || adapter.entry.generation != handle.generation() {
+            return Err(CallError::InvalidHandle(path.clone()));
+        }
+
+        if self.checked_calls {
+            self.validate_symbol_call(symbol, args.len(), None)?;
+        }
 
-        for api in &self.api_cache {
-            full_api.extend(api.clone().into_iter());
+        match handle.slot() {
+            Some(slot) => adapter.call_slot(&adapter.host, slot, args),
+            None => adapter.call_function(&adapter.host, path, args),
         }
+    }
+
+    unsafe fn free_value(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError> {
+        let (path, adapter) = self.symbol_adapter(symbol)?;
+
+        adapter.free_value(&adapter.host, path, value)
+    }
+
+    unsafe fn get_global(&self, symbol: SymbolId, out: *mut Void) -> Result<(), CallError> {
+        self.get_global_definition(symbol)?;
+        let (path, adapter) = self.symbol_adapter(symbol)?;
 
-        full_api
+        adapter.get_global(&adapter.host, path, out)
+    }
+
+    unsafe fn set_global(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError> {
+        let (_, settable) = self.get_global_definition(symbol)?;
+        let (path, adapter) = self.symbol_adapter(symbol)?;
+        if !settable {
+            return Err(CallError::GlobalNotSettable(path.clone()));
+        }
+
+        adapter.set_global(&adapter.host, path, value)
+    }
+
+    /// Callbacks registered through the host itself are owned by the host, so they are never
+    /// revoked. Adapters register their callbacks through the host functions that they are given,
+    /// which make the adapter the owner.
+    fn register_callback(&self, callback: CCallback) -> Result<CallbackHandle, CallError> {
+        Ok(self.callbacks.borrow_mut().register(callback, None))
+    }
+
+    fn retain_callback(&self, handle: CallbackHandle) -> Result<(), CallError> {
+        self.callbacks.borrow_mut().retain(handle)
+    }
+
+    fn release_callback(&self, handle: CallbackHandle) -> Result<(), CallError> {
+        // Release the callback without borrowing the table, because the adapter may call back into
+        // the host while releasing it
+        let callback = self.callbacks.borrow_mut().release(handle)?;
+        if let Some(callback) = callback {
+            unsafe { (callback.callback.release)(callback.callback.user_data) };
+        }
+
+        Ok(())
+    }
+
+    unsafe fn call_callback(
+        &self,
+        handle: CallbackHandle,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        self.with_callback(handle, |callback| {
+            (callback.call)(callback.user_data, args.into()).into_result()
+        })?
+    }
+
+    unsafe fn free_callback_value(
+        &self,
+        handle: CallbackHandle,
+        value: *const Void,
+    ) -> Result<(), CallError> {
+        self.with_callback(handle, |callback| {
+            (callback.free_value)(callback.user_data, value)
+        })
+    }
+}
+
+/// The host functions given to an adapter instance
+///
+/// These are the host functions of the [`Dynamite`] host, except that the callbacks registered
+/// through them are owned by the adapter instance. The owner is passed explicitly instead of being
+/// tracked while the adapter runs, so callbacks that an adapter registers outside of a call from
+/// the host, such as from its own event loop, still belong to it.
+struct AdapterHost<'a> {
+    dynamite: &'a Dynamite,
+    instance: u32,
+}
+
+impl<'a> HostFunctions for AdapterHost<'a> {
+    fn get_full_api(&self) -> Result<ScriptApi, CallError> {
+        self.dynamite.get_full_api()
     }
 
     fn as_dynamite(&self) -> &Dynamite {
-        self
+        self.dynamite
     }
 
-    unsafe fn call_function(&self, path: &TypePath, args: &[*const Void]) -> *const Void {
-        let adapter = self
-            .adapters
-            .get(
-                *self
-                    .type_adapter_index
-                    .get(path)
-                    .ok_or(ScriptApiError::NotFound(path.clone()))
-                    .expect("TODO"),
-            )
-            .expect("Internal error finding adapter");
+    unsafe fn call_function(
+        &self,
+        path: &TypePath,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        self.dynamite.call_function(path, args)
+    }
+
+    fn resolve_symbol(&self, path: &str) -> Result<SymbolId, CallError> {
+        self.dynamite.resolve_symbol(path)
+    }
+
+    fn resolve_function(&self, path: &str) -> Result<FunctionHandle, CallError> {
+        self.dynamite.resolve_function(path)
+    }
+
+    unsafe fn call_handle(
+        &self,
+        handle: FunctionHandle,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        self.dynamite.call_handle(handle, args)
+    }
+
+    unsafe fn free_value(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError> {
+        self.dynamite.free_value(symbol, value)
+    }
+
+    unsafe fn get_global(&self, symbol: SymbolId, out: *mut Void) -> Result<(), CallError> {
+        self.dynamite.get_global(symbol, out)
+    }
+
+    unsafe fn set_global(&self, symbol: SymbolId, value: *const Void) -> Result<(), CallError> {
+        self.dynamite.set_global(symbol, value)
+    }
+
+    fn register_callback(&self, callback: CCallback) -> Result<CallbackHandle, CallError> {
+        Ok(self
+            .dynamite
+            .callbacks
+            .borrow_mut()
+            .register(callback, Some(self.instance)))
+    }
+
+    fn retain_callback(&self, handle: CallbackHandle) -> Result<(), CallError> {
+        self.dynamite.retain_callback(handle)
+    }
+
+    fn release_callback(&self, handle: CallbackHandle) -> Result<(), CallError> {
+        self.dynamite.release_callback(handle)
+    }
+
+    unsafe fn call_callback(
+        &self,
+        handle: CallbackHandle,
+        args: &[*const Void],
+    ) -> Result<*const Void, CallError> {
+        self.dynamite.call_callback(handle, args)
+    }
 
-        adapter.call_function(self, path, args)
+    unsafe fn free_callback_value(
+        &self,
+        handle: CallbackHandle,
+        value: *const Void,
+    ) -> Result<(), CallError> {
+        self.dynamite.free_callback_value(handle, value)
     }
 }
 
@@ -309,12 +1373,14 @@ mod ffi {
     use safer_ffi::prelude::*;
 
     /// C function for getting the full dynamite API
+    ///
+    /// Returns the CBOR serialized `Result<ScriptApi, CallError>`.
     pub(super) extern "C" fn dynamite_get_full_api(dynamite: *const Void) -> repr_c::Vec<u8> {
         let dynamite = unsafe { &*(dynamite as *const Dynamite) };
 
-        serde_cbor::to_vec(&dynamite.get_full_api())
-            .expect("Could not serialize script API")
-            .into()
+        let result = catch_host_panic(|| dynamite.get_full_api());
+
+        encode_cbor_result(&result.and_then(|result| result))
     }
 
     /// C function for calling an API function
@@ -322,17 +1388,182 @@ mod ffi {
         dynamite: *const Void,
         path: str::Ref,
         args: c_slice::Ref<*const Void>,
-    ) -> *const Void {
+    ) -> CCallResult {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        // Resolve the path without copying it into a `TypePath`
+        catch_host_panic(|| unsafe {
+            dynamite
+                .resolve_symbol(path.as_str())
+                .and_then(|symbol| dynamite.call_symbol(symbol, &args))
+        })
+        .and_then(|result| result)
+        .into()
+    }
+
+    /// C function for resolving the symbol of a path
+    ///
+    /// Returns the CBOR serialized `Result<SymbolId, CallError>`.
+    pub(super) extern "C" fn dynamite_resolve_symbol(
+        dynamite: *const Void,
+        path: str::Ref,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| dynamite.resolve_symbol(path.as_str()));
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for resolving a function handle
+    ///
+    /// Returns the CBOR serialized `Result<FunctionHandle, CallError>`.
+    pub(super) extern "C" fn dynamite_resolve_function(
+        dynamite: *const Void,
+        path: str::Ref,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| dynamite.resolve_function(path.as_str()));
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for calling an API function through a handle
+    pub(super) extern "C" fn dynamite_call_handle(
+        dynamite: *const Void,
+        handle: FunctionHandle,
+        args: c_slice::Ref<*const Void>,
+    ) -> CCallResult {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        catch_host_panic(|| unsafe { dynamite.call_handle(handle, &args) })
+            .and_then(|result| result)
+            .into()
+    }
+
+    /// C function for freeing a value returned by an API function
+    ///
+    /// Returns the CBOR serialized `Result<(), CallError>`.
+    pub(super) extern "C" fn dynamite_free_value(
+        dynamite: *const Void,
+        symbol: SymbolId,
+        value: *const Void,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| unsafe { dynamite.free_value(symbol, value) });
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for getting the value of a global
+    ///
+    /// Returns the CBOR serialized `Result<(), CallError>`.
+    pub(super) extern "C" fn dynamite_get_global(
+        dynamite: *const Void,
+        symbol: SymbolId,
+        out: *mut Void,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| unsafe { dynamite.get_global(symbol, out) });
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for setting the value of a global
+    ///
+    /// Returns the CBOR serialized `Result<(), CallError>`.
+    pub(super) extern "C" fn dynamite_set_global(
+        dynamite: *const Void,
+        symbol: SymbolId,
+        value: *const Void,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| unsafe { dynamite.set_global(symbol, value) });
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for registering a callback
+    ///
+    /// Returns the CBOR serialized `Result<CallbackHandle, CallError>`.
+    pub(super) extern "C" fn dynamite_register_callback(
+        dynamite: *const Void,
+        instance: u32,
+        callback: CCallback,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result =
+            catch_host_panic(|| dynamite.adapter_host(instance).register_callback(callback));
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for retaining a callback
+    ///
+    /// Returns the CBOR serialized `Result<(), CallError>`.
+    pub(super) extern "C" fn dynamite_retain_callback(
+        dynamite: *const Void,
+        handle: CallbackHandle,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| dynamite.retain_callback(handle));
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for releasing a callback
+    ///
+    /// Returns the CBOR serialized `Result<(), CallError>`.
+    pub(super) extern "C" fn dynamite_release_callback(
+        dynamite: *const Void,
+        handle: CallbackHandle,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| dynamite.release_callback(handle));
+
+        encode_cbor_result(&result.and_then(|result| result))
+    }
+
+    /// C function for calling a callback
+    pub(super) extern "C" fn dynamite_call_callback(
+        dynamite: *const Void,
+        handle: CallbackHandle,
+        args: c_slice::Ref<*const Void>,
+    ) -> CCallResult {
         let dynamite = unsafe { &*(dynamite as *const Dynamite) };
 
-        // TODO: Get rid of this `to_string` call
-        unsafe { dynamite.call_function(&path.as_ref().to_string(), &args) }
+        catch_host_panic(|| unsafe { dynamite.call_callback(handle, &args) })
+            .and_then(|result| result)
+            .into()
+    }
+
+    /// C function for freeing a value returned by a callback
+    ///
+    /// Returns the CBOR serialized `Result<(), CallError>`.
+    pub(super) extern "C" fn dynamite_free_callback_value(
+        dynamite: *const Void,
+        handle: CallbackHandle,
+        value: *const Void,
+    ) -> repr_c::Vec<u8> {
+        let dynamite = unsafe { &*(dynamite as *const Dynamite) };
+
+        let result = catch_host_panic(|| unsafe { dynamite.free_callback_value(handle, value) });
+
+        encode_cbor_result(&result.and_then(|result| result))
     }
 }
 
 pub use error::*;
 mod error {
     use super::*;
+    use serde::{Deserialize, Serialize};
 
     /// An error that ocurred when trying to access the scripting API
     #[derive(thiserror::Error, Debug)]
@@ -341,6 +1572,19 @@ mod error {
         ApiError(#[from] ScriptApiError),
         #[error("Error loading dynamic library: {0}")]
         DynamicLibError(#[from] dlopen::Error),
+        #[error("Language adapter error: {0}")]
+        AdapterError(#[from] CallError),
+        #[error("Language adapter {0} was not found")]
+        AdapterNotFound(AdapterId),
+        #[error("Language adapter {0} was not loaded from a dynamic library")]
+        NotReloadable(AdapterId),
+        #[error("IO error: {0}")]
+        Io(#[from] std::io::Error),
+        #[error(
+            "Language adapter `{}` is not compatible with this version of Dynamite: {reason}",
+            path.display()
+        )]
+        AbiMismatch { path: PathBuf, reason: String },
     }
 
     /// An error that ocurred when trying to access the scripting API
@@ -350,5 +1594,675 @@ mod error {
         NotFound(TypePath),
         #[error("Loaded adapter re-defineds type already defined by another adapter: {0}")]
         TypeRedefined(TypePath),
+        #[error("Invalid type path: {0}")]
+        InvalidPath(#[from] TypePathError),
+        #[error("Path `{path}` is outside of the adapter's namespace `{namespace}`")]
+        OutsideNamespace { path: TypePath, namespace: TypePath },
+        #[error("Namespace `{0}` overlaps with the namespace or the API of another adapter")]
+        NamespaceTaken(TypePath),
+        #[error("Path `{path}` is inside of the namespace `{namespace}` of another adapter")]
+        InsideNamespace { path: TypePath, namespace: TypePath },
+        #[error("Methods can only be added to structs, but `{0}` is not a struct")]
+        NotAStruct(TypePath),
+        #[error("Type `{path}` has an invalid layout: {error}")]
+        InvalidLayout {
+            path: TypePath,
+            #[source]
+            error: LayoutError,
+        },
+    }
+
+    /// An error in the syntax of a [`TypePath`]
+    #[derive(thiserror::Error, Debug)]
+    pub enum TypePathError {
+        #[error("Type paths cannot be empty")]
+        Empty,
+        #[error("Type path `{0}` has unbalanced brackets")]
+        UnbalancedBrackets(TypePath),
+        #[error("Type path `{path}` has an invalid segment `{segment}`")]
+        InvalidSegment { path: TypePath, segment: String },
+    }
+
+    /// An error in the layout of a [`StructDefinition`] or an [`EnumDefinition`]
+    #[derive(thiserror::Error, Debug)]
+    pub enum LayoutError {
+        #[error("Size {size} is not a multiple of the alignment {align}")]
+        SizeNotPadded { size: usize, align: usize },
+        #[error("Field `{field}` at offset {offset} is not aligned to {align} bytes")]
+        FieldMisaligned {
+            field: String,
+            offset: usize,
+            align: usize,
+        },
+        #[error("Field `{field}` ends at byte {end}, past the end of the type of size {size}")]
+        FieldOutOfBounds {
+            field: String,
+            end: usize,
+            size: usize,
+        },
+        #[error("Field `{field}` at offset {offset} overflows the address space")]
+        FieldOverflow { field: String, offset: usize },
+        #[error("Fields `{first}` and `{second}` overlap")]
+        FieldsOverlap { first: String, second: String },
+        #[error("Enum tag type `{0}` is not a primitive integer type")]
+        InvalidTagType(TypePath),
+    }
+
+    /// An error that ocurred when calling a function through the scripting API
+    ///
+    /// Call errors are serializable so that they can be sent across the dynamic library boundary in
+    /// the CBOR encoded `error` field of a [`CCallResult`].
+    #[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone)]
+    pub enum CallError {
+        #[error("The requested function was not found: {0}")]
+        NotFound(TypePath),
+        #[error("Function `{path}` takes {expected} arguments but was called with {found}")]
+        WrongArity {
+            path: TypePath,
+            expected: usize,
+            found: usize,
+        },
+        #[error(
+            "Argument {index} of function `{path}` must be a `{expected}` but got a `{found}`"
+        )]
+        TypeMismatch {
+            path: TypePath,
+            index: usize,
+            expected: TypePath,
+            found: TypePath,
+        },
+        #[error("Function `{0}` does not return a value")]
+        NoReturnValue(TypePath),
+        #[error("Function `{0}` does not return values owned by the caller")]
+        NotOwnedByCaller(TypePath),
+        #[error("Function `{path}` returns a `{declared}` but a `{requested}` was requested")]
+        ReturnTypeMismatch {
+            path: TypePath,
+            declared: TypePath,
+            requested: TypePath,
+        },
+        #[error("`{0}` is not a function")]
+        NotAFunction(TypePath),
+        #[error("The function handle for `{0}` is no longer valid")]
+        InvalidHandle(TypePath),
+        #[error("`{0}` is not a global")]
+        NotAGlobal(TypePath),
+        #[error("Global `{0}` cannot be set")]
+        GlobalNotSettable(TypePath),
+        #[error("Global `{path}` is a `{declared}` but was accessed as a `{requested}`")]
+        GlobalTypeMismatch {
+            path: TypePath,
+            declared: TypePath,
+            requested: TypePath,
+        },
+        #[error("No callback with id {0} is registered")]
+        CallbackNotFound(u64),
+        #[error("The callback handle {0} is no longer valid because its adapter was removed")]
+        InvalidCallbackHandle(u64),
+        #[error("No symbol with id {0} is registered")]
+        SymbolNotFound(u32),
+        #[error("Script error: {0}")]
+        Script(#[from] ScriptError),
+        #[error("Language adapter panicked: {0}")]
+        AdapterPanic(String),
+        #[error("Dynamite host panicked: {0}")]
+        HostPanic(String),
+        #[error("Language adapter error: {0}")]
+        AdapterError(String),
+    }
+
+    /// An error returned by a fallible function in the scripting API
+    ///
+    /// This is the standard error representation shared by all adapters. Adapters should turn it
+    /// into an exception or error value in the calling script, and turn script errors into it when
+    /// a script function fails.
+    #[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    #[error("{error_type}: {message}")]
+    pub struct ScriptError {
+        /// The name of the type of the error
+        pub error_type: String,
+        /// The error message
+        pub message: String,
+    }
+
+    impl ScriptError {
+        /// Create a script error from any error that implements [`Display`][std::fmt::Display]
+        pub fn new<E: std::fmt::Display + ?Sized>(error: &E) -> Self {
+            Self {
+                error_type: std::any::type_name::<E>().into(),
+                message: error.to_string(),
+            }
+        }
+    }
+
+    /// Run a function, catching any panic and turning it into a [`CallError::AdapterPanic`]
+    ///
+    /// Panics must not unwind across an `extern "C"` boundary, so this is used to wrap the body of
+    /// every C function exported by the [`language_adapter`] macro. The C functions exported by
+    /// the Dynamite host report panics as a [`CallError::HostPanic`] instead.
+    pub fn catch_panic<R, F: FnOnce() -> R>(f: F) -> Result<R, CallError> {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+            .map_err(|payload| CallError::AdapterPanic(panic_message(payload)))
+    }
+
+    /// Run a function in the host, catching any panic and turning it into a
+    /// [`CallError::HostPanic`]
+    pub(crate) fn catch_host_panic<R, F: FnOnce() -> R>(f: F) -> Result<R, CallError> {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+            .map_err(|payload| CallError::HostPanic(panic_message(payload)))
+    }
+
+    /// Get the message of a caught panic
+    fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown panic payload".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// A language adapter that provides a list of functions that don't do anything
+    ///
+    /// Each function has a slot, which is returned as the pointer to its return value when the
+    /// function is called through the slot. Calls by path return a null pointer instead.
+    struct TestAdapter {
+        paths: RefCell<Vec<TypePath>>,
+        /// The paths that the adapter provides after its scripts are reloaded
+        reloaded_paths: Vec<TypePath>,
+        /// The arguments taken by every function
+        arguments: Vec<FunctionArgument>,
+    }
+
+    impl TestAdapter {
+        fn new(paths: &[&str]) -> Box<Self> {
+            Self::with_reloaded_paths(paths, paths)
+        }
+
+        fn with_reloaded_paths(paths: &[&str], reloaded_paths: &[&str]) -> Box<Self> {
+            let to_paths = |paths: &[&str]| paths.iter().map(|&path| path.into()).collect();
+
+            Box::new(Self {
+                paths: RefCell::new(to_paths(paths)),
+                reloaded_paths: to_paths(reloaded_paths),
+                arguments: Vec::new(),
+            })
+        }
+
+        fn with_arguments(mut self: Box<Self>, arguments: Vec<FunctionArgument>) -> Box<Self> {
+            self.arguments = arguments;
+            self
+        }
+
+        fn function_definition(&self) -> FunctionDefinition {
+            FunctionDefinition {
+                arguments: self.arguments.clone(),
+                return_type: None,
+                return_ownership: ReturnOwnership::Borrowed,
+                fallible: false,
+                optional_return: false,
+            }
+        }
+    }
+
+    impl LanguageAdapter for TestAdapter {
+        fn get_api(&self, _host_functions: &dyn HostFunctions) -> ScriptApi {
+            self.paths
+                .borrow()
+                .iter()
+                .map(|path| {
+                    let definition = self.function_definition();
+                    (path.clone(), ScriptType::Function(definition))
+                })
+                .collect()
+        }
+
+        unsafe fn call_function(
+            &self,
+            _host_functions: &dyn HostFunctions,
+            path: &str,
+            _args: &[*const Void],
+        ) -> Result<*const Void, CallError> {
+            if self.paths.borrow().iter().any(|other| other == path) {
+                Ok(std::ptr::null())
+            } else {
+                Err(CallError::NotFound(path.into()))
+            }
+        }
+
+        fn resolve_function(
+            &self,
+            _host_functions: &dyn HostFunctions,
+            path: &str,
+        ) -> Result<Option<u64>, CallError> {
+            Ok(self
+                .paths
+                .borrow()
+                .iter()
+                .position(|other| other == path)
+                .map(|slot| slot as u64))
+        }
+
+        unsafe fn call_slot(
+            &self,
+            _host_functions: &dyn HostFunctions,
+            slot: u64,
+            _args: &[*const Void],
+        ) -> Result<*const Void, CallError> {
+            Ok(slot_pointer(slot))
+        }
+
+        fn reload_scripts(&self, _host_functions: &dyn HostFunctions) -> Result<(), CallError> {
+            *self.paths.borrow_mut() = self.reloaded_paths.clone();
+
+            Ok(())
+        }
+    }
+
+    /// Get the pointer returned by calls through the given slot of a [`TestAdapter`]
+    fn slot_pointer(slot: u64) -> *const Void {
+        (slot as usize + 1) as *const Void
+    }
+
+    /// Add an adapter with a `game::jump(height: f32, times: u32)` function
+    fn dynamite_with_jump() -> Dynamite {
+        let mut dynamite = Dynamite::new();
+        let adapter = TestAdapter::new(&["game::jump"]).with_arguments(vec![
+            FunctionArgument::new("height", f32::script_path()),
+            FunctionArgument::new("times", u32::script_path()),
+        ]);
+        dynamite.add_language_adapter(adapter, "game").unwrap();
+
+        dynamite
+    }
+
+    #[test]
+    fn calls_are_validated_against_the_function_definition() {
+        let dynamite = dynamite_with_jump();
+        let path = "game::jump".into();
+        let f32_path = f32::script_path();
+        let u32_path = u32::script_path();
+
+        dynamite.validate_call(&path, 2, None).unwrap();
+        dynamite
+            .validate_call(&path, 2, Some(&[f32_path.clone(), u32_path.clone()]))
+            .unwrap();
+
+        assert!(matches!(
+            dynamite.validate_call(&path, 1, None),
+            Err(CallError::WrongArity {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            dynamite.validate_call(&path, 2, Some(std::slice::from_ref(&f32_path))),
+            Err(CallError::WrongArity {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+        match dynamite.validate_call(&path, 2, Some(&[f32_path, i32::script_path()])) {
+            Err(CallError::TypeMismatch {
+                index,
+                expected,
+                found,
+                ..
+            }) => {
+                assert_eq!(index, 1);
+                assert_eq!(expected, u32_path);
+                assert_eq!(found, i32::script_path());
+            }
+            other => panic!("Expected a type mismatch, got {:?}", other),
+        }
+        assert!(matches!(
+            dynamite.validate_call(&"game::run".into(), 0, None),
+            Err(CallError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn checked_function_calls_are_always_validated() {
+        let dynamite = dynamite_with_jump();
+        let path = "game::jump".into();
+        let args = [std::ptr::null(); 2];
+
+        assert!(matches!(
+            unsafe { dynamite.call_function_checked(&path, &args[..1], None) },
+            Err(CallError::WrongArity {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            unsafe {
+                dynamite.call_function_checked(
+                    &path,
+                    &args,
+                    Some(&[u32::script_path(), u32::script_path()]),
+                )
+            },
+            Err(CallError::TypeMismatch { index: 0, .. })
+        ));
+        assert!(unsafe {
+            dynamite.call_function_checked(
+                &path,
+                &args,
+                Some(&[f32::script_path(), u32::script_path()]),
+            )
+        }
+        .unwrap()
+        .is_null());
+    }
+
+    #[test]
+    fn checked_calls_validate_unchecked_calls() {
+        let mut dynamite = dynamite_with_jump();
+        let path = "game::jump".into();
+        let handle = dynamite.resolve_function("game::jump").unwrap();
+
+        // Calls with the wrong number of arguments are passed to the adapter by default
+        assert!(unsafe { dynamite.call_function(&path, &[]) }
+            .unwrap()
+            .is_null());
+        assert_eq!(
+            unsafe { dynamite.call_handle(handle, &[]) }.unwrap(),
+            slot_pointer(0)
+        );
+
+        dynamite.set_checked_calls(true);
+        assert!(matches!(
+            unsafe { dynamite.call_function(&path, &[]) },
+            Err(CallError::WrongArity {
+                expected: 2,
+                found: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            unsafe { dynamite.call_handle(handle, &[]) },
+            Err(CallError::WrongArity {
+                expected: 2,
+                found: 0,
+                ..
+            })
+        ));
+        assert!(
+            unsafe { dynamite.call_function(&path, &[std::ptr::null(); 2]) }
+                .unwrap()
+                .is_null()
+        );
+    }
+
+    #[test]
+    fn namespaces_may_not_overlap() {
+        let mut dynamite = Dynamite::new();
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["game::run"]), "game")
+            .unwrap();
+
+        for namespace in &["game", "game::physics"] {
+            assert!(matches!(
+                dynamite.add_language_adapter(TestAdapter::new(&[]), *namespace),
+                Err(ScriptApiError::NamespaceTaken(_))
+            ));
+        }
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["gameplay::run"]), "gameplay")
+            .unwrap();
+    }
+
+    #[test]
+    fn namespaces_may_not_overlap_registered_paths() {
+        let mut dynamite = Dynamite::new();
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["game::physics::step"]), "game::physics")
+            .unwrap();
+
+        // Namespaces around or inside of the registered path are both taken
+        for namespace in &["game", "game::physics::step", "game::physics::step::inner"] {
+            assert!(matches!(
+                dynamite.add_language_adapter(TestAdapter::new(&[]), *namespace),
+                Err(ScriptApiError::NamespaceTaken(_))
+            ));
+        }
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["game::audio::play"]), "game::audio")
+            .unwrap();
+    }
+
+    #[test]
+    fn symbols_are_kept_when_an_adapter_is_removed() {
+        let mut dynamite = Dynamite::new();
+        let id = dynamite
+            .add_language_adapter(TestAdapter::new(&["game::run"]), "game")
+            .unwrap();
+        let symbol = dynamite.resolve_symbol("game::run").unwrap();
+
+        dynamite.remove_language_adapter(id).unwrap();
+        assert_eq!(dynamite.symbol_path(symbol).unwrap(), "game::run");
+        assert!(dynamite.get_script_type(symbol).is_none());
+        assert!(dynamite.get_full_api().unwrap().is_empty());
+
+        // The path gets its old symbol back when it is added again
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["game::stop", "game::run"]), "game")
+            .unwrap();
+        assert_eq!(dynamite.resolve_symbol("game::run").unwrap(), symbol);
+        assert!(dynamite.get_script_type(symbol).is_some());
+        assert_ne!(dynamite.resolve_symbol("game::stop").unwrap(), symbol);
+    }
+
+    #[test]
+    fn function_handles_call_through_the_adapter_slot() {
+        let mut dynamite = Dynamite::new();
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["game::run", "game::stop"]), "game")
+            .unwrap();
+
+        let handle = dynamite.resolve_function("game::stop").unwrap();
+        assert_eq!(
+            handle.symbol(),
+            dynamite.resolve_symbol("game::stop").unwrap()
+        );
+        assert_eq!(handle.slot(), Some(1));
+        assert_eq!(
+            unsafe { dynamite.call_handle(handle, &[]) }.unwrap(),
+            slot_pointer(1)
+        );
+        assert!(unsafe { dynamite.call_function(&"game::stop".into(), &[]) }
+            .unwrap()
+            .is_null());
+
+        assert!(matches!(
+            dynamite.resolve_function("game::jump"),
+            Err(CallError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn function_handles_are_invalidated_when_their_adapter_is_removed() {
+        let mut dynamite = Dynamite::new();
+        let id = dynamite
+            .add_language_adapter(TestAdapter::new(&["game::run"]), "game")
+            .unwrap();
+        let handle = dynamite.resolve_function("game::run").unwrap();
+
+        dynamite.remove_language_adapter(id).unwrap();
+        assert!(matches!(
+            unsafe { dynamite.call_handle(handle, &[]) },
+            Err(CallError::NotFound(_))
+        ));
+
+        // The handle stays invalid when another adapter provides the function again
+        dynamite
+            .add_language_adapter(TestAdapter::new(&["game::run"]), "game")
+            .unwrap();
+        assert!(matches!(
+            unsafe { dynamite.call_handle(handle, &[]) },
+            Err(CallError::InvalidHandle(_))
+        ));
+        let handle = dynamite.resolve_function("game::run").unwrap();
+        assert!(unsafe { dynamite.call_handle(handle, &[]) }.is_ok());
+    }
+
+    #[test]
+    fn reloading_scripts_invalidates_function_handles() {
+        let mut dynamite = Dynamite::new();
+        let id = dynamite
+            .add_language_adapter(
+                TestAdapter::with_reloaded_paths(
+                    &["game::run", "game::stop"],
+                    &["game::jump", "game::run"],
+                ),
+                "game",
+            )
+            .unwrap();
+        let handle = dynamite.resolve_function("game::run").unwrap();
+
+        let diff = dynamite.reload_scripts(id).unwrap();
+        assert_eq!(diff.added, ["game::jump"]);
+        assert_eq!(diff.removed, ["game::stop"]);
+        assert!(diff.changed.is_empty());
+
+        // The function may have moved to another slot, so the handle must be resolved again
+        assert!(matches!(
+            unsafe { dynamite.call_handle(handle, &[]) },
+            Err(CallError::InvalidHandle(_))
+        ));
+        let handle = dynamite.resolve_function("game::run").unwrap();
+        assert_eq!(
+            unsafe { dynamite.call_handle(handle, &[]) }.unwrap(),
+            slot_pointer(1)
+        );
+        assert!(matches!(
+            dynamite.resolve_function("game::stop"),
+            Err(CallError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn adapter_libraries_access_the_host_by_symbol() {
+        let dynamite = dynamite_with_jump();
+        let host = RemoteHostFunctions {
+            dynamite: &dynamite as *const Dynamite as *const Void,
+            instance: 0,
+            pointers: Dynamite::host_function_pointers(),
+        };
+
+        let symbol = host.resolve_symbol("game::jump").unwrap();
+        assert_eq!(symbol, dynamite.resolve_symbol("game::jump").unwrap());
+        assert!(matches!(
+            host.resolve_symbol("game::run"),
+            Err(CallError::NotFound(_))
+        ));
+
+        // The symbol is sent to the host as is, without its path
+        let mut out = 0u32;
+        match unsafe { host.get_global(symbol, &mut out as *mut u32 as *mut Void) } {
+            Err(CallError::NotAGlobal(path)) => assert_eq!(path, "game::jump"),
+            other => panic!("Expected a function, got {:?}", other),
+        }
+        assert!(matches!(
+            unsafe { host.set_global(SymbolId { id: 100 }, &out as *const u32 as *const Void) },
+            Err(CallError::SymbolNotFound(100))
+        ));
+    }
+
+    /// Get a callback that doesn't do anything
+    fn callback() -> CCallback {
+        unsafe extern "C" fn call(
+            _user_data: *const Void,
+            _args: safer_ffi::prelude::c_slice::Ref<*const Void>,
+        ) -> CCallResult {
+            Ok(std::ptr::null()).into()
+        }
+        unsafe extern "C" fn free_value(_user_data: *const Void, _value: *const Void) {}
+        unsafe extern "C" fn release(_user_data: *const Void) {}
+
+        CCallback {
+            user_data: std::ptr::null(),
+            call,
+            free_value,
+            release,
+        }
+    }
+
+    /// A language adapter that registers a callback through its host functions when its API is
+    /// loaded
+    struct CallbackAdapter {
+        handle: std::rc::Rc<std::cell::Cell<Option<CallbackHandle>>>,
+    }
+
+    impl LanguageAdapter for CallbackAdapter {
+        fn get_api(&self, host_functions: &dyn HostFunctions) -> ScriptApi {
+            let handle = host_functions.register_callback(callback()).unwrap();
+            self.handle.set(Some(handle));
+
+            ScriptApi::default()
+        }
+
+        unsafe fn call_function(
+            &self,
+            _host_functions: &dyn HostFunctions,
+            path: &str,
+            _args: &[*const Void],
+        ) -> Result<*const Void, CallError> {
+            Err(CallError::NotFound(path.into()))
+        }
+    }
+
+    #[test]
+    fn callbacks_registered_by_an_adapter_are_revoked_when_it_is_removed() {
+        let mut dynamite = Dynamite::new();
+        let handle = std::rc::Rc::new(std::cell::Cell::new(None));
+        let id = dynamite
+            .add_language_adapter(
+                Box::new(CallbackAdapter {
+                    handle: handle.clone(),
+                }),
+                "game",
+            )
+            .unwrap();
+        let adapter_callback = handle.get().unwrap();
+        let host_callback = dynamite.register_callback(callback()).unwrap();
+
+        // Another adapter may hold a reference to the callback, but it still belongs to the
+        // adapter that registered it
+        dynamite.retain_callback(adapter_callback).unwrap();
+        dynamite.remove_language_adapter(id).unwrap();
+        assert!(matches!(
+            unsafe { dynamite.call_callback(adapter_callback, &[]) },
+            Err(CallError::InvalidCallbackHandle(_))
+        ));
+        assert!(unsafe { dynamite.call_callback(host_callback, &[]) }.is_ok());
+    }
+
+    #[test]
+    fn paths_must_be_inside_of_the_namespace() {
+        let mut dynamite = Dynamite::new();
+
+        assert!(matches!(
+            dynamite.add_language_adapter(TestAdapter::new(&["gameplay::run"]), "game"),
+            Err(ScriptApiError::OutsideNamespace { .. })
+        ));
+        assert!(matches!(
+            dynamite.add_language_adapter(TestAdapter::new(&["game::"]), "game"),
+            Err(ScriptApiError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            dynamite.add_language_adapter(TestAdapter::new(&[]), "game::"),
+            Err(ScriptApiError::InvalidPath(_))
+        ));
     }
 }
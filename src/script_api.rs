@@ -3,11 +3,16 @@
 use safer_ffi::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use std::{borrow::Cow, collections::HashMap};
+use crate::LayoutError;
+
+use std::{borrow::Cow, collections::HashMap, convert::TryFrom};
 
 // Core trait implementations
 mod impls;
-pub use impls::*;
+
+// Structured type paths
+mod type_path;
+pub use type_path::*;
 
 pub use ty::Void;
 mod ty {
@@ -24,20 +29,122 @@ mod ty {
 /// A registry of scripted types mapping their unique module path to the type definition.
 pub type ScriptApi = HashMap<TypePath, ScriptType>;
 
-/// The path to a scripted type, i.e. the "module" path such as "mygame::physics::RigidBody".
-pub type TypePath = String;
+/// The difference between two versions of a [`ScriptApi`], such as before and after reloading a
+/// language adapter
+///
+/// The paths in each list are sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ApiDiff {
+    /// Paths that are only in the new API
+    pub added: Vec<TypePath>,
+    /// Paths that are only in the old API
+    pub removed: Vec<TypePath>,
+    /// Paths that are in both APIs but with a different [`ScriptType`]
+    pub changed: Vec<TypePath>,
+}
+
+impl ApiDiff {
+    /// Compare two versions of an API
+    pub fn new(old: &ScriptApi, new: &ScriptApi) -> Self {
+        let mut diff = ApiDiff::default();
+
+        for (path, script_type) in new {
+            match old.get(path) {
+                None => diff.added.push(path.clone()),
+                Some(old_type) if old_type != script_type => diff.changed.push(path.clone()),
+                Some(_) => (),
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|path| !new.contains_key(*path))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+
+        diff
+    }
+
+    /// Whether the two APIs are the same
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
 
 /// A script-loaded type
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum ScriptType {
     /// A struct definition
     Struct(StructDefinition),
     /// A function definition
     Function(FunctionDefinition),
+    /// An enum definition
+    Enum(EnumDefinition),
+    /// A constant value that scripts can read directly from the API
+    Constant {
+        /// The type of the constant
+        type_path: TypePath,
+        /// The value of the constant
+        value: LiteralValue,
+    },
+    /// A global variable owned by a language adapter
+    ///
+    /// Globals are accessed with [`HostFunctions::get_global`] and [`HostFunctions::set_global`],
+    /// which are routed to the adapter that provides the global.
+    ///
+    /// [`HostFunctions::get_global`]: crate::HostFunctions::get_global
+    /// [`HostFunctions::set_global`]: crate::HostFunctions::set_global
+    Global {
+        /// The type of the global
+        type_path: TypePath,
+        /// Whether scripts may set the global
+        settable: bool,
+    },
     /// A primitive type
     Primitive(Primitive),
+    /// An opaque handle to a value owned by a language adapter
+    ///
+    /// Handles are passed around as [`FFIObj`]s, which any adapter can clone, drop, or downcast to
+    /// the original Rust type, but the contents of which are not visible to the scripting API.
+    ///
+    /// [`FFIObj`]: crate::FFIObj
+    Handle,
 }
 
+/// Implemented by Rust types that can be described in a [`ScriptApi`]
+///
+/// # Built-in Types
+///
+/// Built-in types are registered under the `std` namespace using their Rust name, or `unit` for
+/// `()`. Strings cannot cross the FFI boundary as Rust `&str`s or `String`s, so they are represented
+/// by their [`safer_ffi`] equivalents. `str` shares the path of [`str::Ref`] so that `&str`
+/// arguments and constants can be described, but `String` has no implementation because its layout
+/// is not the layout of [`repr_c::String`]. Stockpile functions convert `&str` arguments from
+/// [`str::Ref`]s and `String` arguments and return values to and from [`repr_c::String`]s.
+///
+/// | Rust Type                                    | Path                    |
+/// |----------------------------------------------|-------------------------|
+/// | `u8`, `i32`, `f64`, etc.                     | `std::u8`, `std::i32`, `std::f64`, etc. |
+/// | `bool`                                       | `std::bool`             |
+/// | `char`                                       | `std::char`             |
+/// | `()`                                         | `std::unit`             |
+/// | `*const T`, `*mut T`                         | `std::pointer`          |
+/// | `str`, [`str::Ref`] ( a pointer and length ) | `std::str`              |
+/// | [`repr_c::String`] ( a pointer, length, and capacity ) | `std::String` |
+/// | `[T; N]`                                     | `std::Array<T, N>`      |
+/// | [`c_slice::Ref<T>`], [`c_slice::Mut<T>`] ( a pointer and length ) | `std::Slice<T>` |
+/// | [`ScriptVec<T>`]                             | `std::Vec<T>`           |
+///
+/// In the paths of the generic types, `T` is replaced with the path of the element type, such as
+/// `std::Vec<std::f32>`.
+///
+/// [`ScriptVec<T>`]: crate::ScriptVec
+///
+/// [`Primitive::CChar`] uses the path `std::c_char`, but because `c_char` is an alias for either
+/// `i8` or `u8` it cannot have its own [`HasScriptType`] implementation.
 pub trait HasScriptType {
     fn script_type() -> ScriptType;
     fn script_path() -> TypePath;
@@ -45,14 +152,14 @@ pub trait HasScriptType {
 
 /// The information necessary to define a component including the component ID and the memory
 /// layout.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct StructDefinition {
     /// The size and alignment of the component
     pub layout: DataLayout,
     /// The type of component this is
     pub component_type: DataType,
-    /// The definitions of the methods associated to to the [`method_pointers`] with the same index.
-    pub method_definitions: Vec<FunctionDefinition>,
+    /// The definitions of the methods associated to the struct
+    pub method_definitions: Vec<MethodDefinition>,
 }
 
 impl HasDataLayout for StructDefinition {
@@ -61,13 +168,197 @@ impl HasDataLayout for StructDefinition {
     }
 }
 
+impl StructDefinition {
+    /// Get the field with the given name, if this is a [`DataType::Struct`]
+    pub fn field(&self, name: &str) -> Option<&StructField> {
+        match &self.component_type {
+            DataType::Struct { fields } => fields.iter().find(|field| field.name == name),
+            _ => None,
+        }
+    }
+
+    /// Validate that the fields of a [`DataType::Struct`] fit in the struct's [`DataLayout`]
+    ///
+    /// This makes sure that every field is properly aligned, that no field extends past the end of
+    /// the struct or overlaps with another field, and that the struct's size is padded to a
+    /// multiple of its alignment.
+    pub fn validate_layout(&self) -> Result<(), LayoutError> {
+        match &self.component_type {
+            DataType::Struct { fields } => validate_fields(self.layout, fields),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Validate that `fields` fit in a type with the given `layout`
+fn validate_fields(layout: DataLayout, fields: &[StructField]) -> Result<(), LayoutError> {
+    if layout.size() % layout.align() != 0 {
+        return Err(LayoutError::SizeNotPadded {
+            size: layout.size(),
+            align: layout.align(),
+        });
+    }
+
+    for field in fields {
+        if field.offset % field.layout.align() != 0 {
+            return Err(LayoutError::FieldMisaligned {
+                field: field.name.clone(),
+                offset: field.offset,
+                align: field.layout.align(),
+            });
+        }
+
+        let end = field
+            .offset
+            .checked_add(field.layout.size())
+            .ok_or_else(|| LayoutError::FieldOverflow {
+                field: field.name.clone(),
+                offset: field.offset,
+            })?;
+        if end > layout.size() {
+            return Err(LayoutError::FieldOutOfBounds {
+                field: field.name.clone(),
+                end,
+                size: layout.size(),
+            });
+        }
+    }
+
+    // Check neighboring fields for overlap, ignoring zero-sized fields. The ends of the fields are
+    // within the size of the type, so they can't overflow.
+    let mut sorted_fields = fields
+        .iter()
+        .filter(|field| field.layout.size() > 0)
+        .collect::<Vec<_>>();
+    sorted_fields.sort_by_key(|field| field.offset);
+    for pair in sorted_fields.windows(2) {
+        if pair[0].offset + pair[0].layout.size() > pair[1].offset {
+            return Err(LayoutError::FieldsOverlap {
+                first: pair[0].name.clone(),
+                second: pair[1].name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The definition of an enum, which may be either a C-like enum or a tagged union
+///
+/// Enums are laid out according to [RFC 2195]: the tag comes first, followed by the fields of the
+/// active variant at the offsets given by that variant's [`StructField`]s. C-like enums are just a
+/// tag.
+///
+/// [RFC 2195]: https://rust-lang.github.io/rfcs/2195-really-tagged-unions.html
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnumDefinition {
+    /// The size and alignment of the enum
+    pub layout: DataLayout,
+    /// The type of the tag at the start of the enum, which is always a primitive integer type
+    pub tag_type: TypePath,
+    /// The variants of the enum, in declaration order
+    pub variants: Vec<EnumVariant>,
+}
+
+impl HasDataLayout for EnumDefinition {
+    fn get_data_layout(&self) -> DataLayout {
+        self.layout
+    }
+}
+
+impl EnumDefinition {
+    /// Get the variant with the given name
+    pub fn variant(&self, name: &str) -> Option<&EnumVariant> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    /// Get the variant with the given discriminant, such as the value of an enum's tag
+    pub fn variant_by_discriminant(&self, discriminant: i64) -> Option<&EnumVariant> {
+        self.variants
+            .iter()
+            .find(|variant| variant.discriminant == discriminant)
+    }
+
+    /// Whether the enum is a C-like enum, which means none of its variants have fields
+    pub fn is_c_like(&self) -> bool {
+        self.variants
+            .iter()
+            .all(|variant| variant.fields.is_empty())
+    }
+
+    /// Get the layout of the enum's tag, if the tag type is a primitive integer type
+    pub fn tag_layout(&self) -> Option<DataLayout> {
+        let primitive = match self.tag_type.as_str() {
+            "std::u8" => Primitive::U8,
+            "std::u16" => Primitive::U16,
+            "std::u32" => Primitive::U32,
+            "std::u64" => Primitive::U64,
+            "std::usize" => Primitive::Usize,
+            "std::i8" => Primitive::I8,
+            "std::i16" => Primitive::I16,
+            "std::i32" => Primitive::I32,
+            "std::i64" => Primitive::I64,
+            "std::isize" => Primitive::Isize,
+            _ => return None,
+        };
+
+        Some(primitive.get_data_layout())
+    }
+
+    /// Validate that the tag and the fields of every variant fit in the enum's [`DataLayout`]
+    ///
+    /// The same checks as for [`StructDefinition::validate_layout`] are done for each variant,
+    /// with the tag as an extra field at offset zero that no other field may overlap. Fields are
+    /// named `Variant::field` in errors.
+    pub fn validate_layout(&self) -> Result<(), LayoutError> {
+        let tag_layout = self
+            .tag_layout()
+            .ok_or_else(|| LayoutError::InvalidTagType(self.tag_type.clone()))?;
+        let tag = StructField {
+            name: "tag".to_string(),
+            type_path: self.tag_type.clone(),
+            offset: 0,
+            layout: tag_layout,
+        };
+
+        validate_fields(self.layout, std::slice::from_ref(&tag))?;
+        for variant in &self.variants {
+            let fields = std::iter::once(tag.clone())
+                .chain(variant.fields.iter().map(|field| StructField {
+                    name: format!("{}::{}", variant.name, field.name),
+                    ..field.clone()
+                }))
+                .collect::<Vec<_>>();
+            validate_fields(self.layout, &fields)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A variant of an enum
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    /// The name of the variant
+    pub name: String,
+    /// The value of the enum's tag for this variant
+    ///
+    /// Tags of unsigned types that don't fit in an `i64` are stored as their bit pattern.
+    pub discriminant: i64,
+    /// The fields of the variant, with offsets from the start of the enum
+    pub fields: Vec<StructField>,
+}
+
 /// Implemented by types that can define a [`DataLayout`]
 pub trait HasDataLayout {
     fn get_data_layout(&self) -> DataLayout;
 }
 
 /// A type memory layout
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+///
+/// Deserialized layouts are validated with [`DataLayout::from_size_align`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "RawDataLayout")]
 pub struct DataLayout {
     /// The number of bytes the type takes up
     size: usize,
@@ -75,6 +366,21 @@ pub struct DataLayout {
     align: usize,
 }
 
+/// A [`DataLayout`] that has not been validated yet
+#[derive(Deserialize)]
+struct RawDataLayout {
+    size: usize,
+    align: usize,
+}
+
+impl TryFrom<RawDataLayout> for DataLayout {
+    type Error = std::alloc::LayoutError;
+
+    fn try_from(layout: RawDataLayout) -> Result<Self, Self::Error> {
+        Self::from_size_align(layout.size, layout.align)
+    }
+}
+
 impl DataLayout {
     pub fn from_size_align(size: usize, align: usize) -> Result<Self, std::alloc::LayoutError> {
         // TODO: Better way to verify the layout parameters?
@@ -83,6 +389,11 @@ impl DataLayout {
         Ok(Self { size, align })
     }
 
+    /// Get the layout of a Rust type
+    pub fn of<T>() -> Self {
+        std::alloc::Layout::new::<T>().into()
+    }
+
     pub fn size(&self) -> usize {
         self.size
     }
@@ -92,21 +403,69 @@ impl DataLayout {
     }
 }
 
+impl From<std::alloc::Layout> for DataLayout {
+    fn from(layout: std::alloc::Layout) -> Self {
+        Self {
+            size: layout.size(),
+            align: layout.align(),
+        }
+    }
+}
+
 /// A data type, usually of a function argument or return value
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum DataType {
     /// A pointer to a different type
     Pointer(Box<ScriptType>),
-    /// A struct with string field keys
-    Struct {
-        fields: HashMap<String, StructDefinition>,
-    },
+    /// A struct with fields in declaration order
+    Struct { fields: Vec<StructField> },
+    /// A fixed size array of `len` elements, laid out like a Rust `[T; N]`
+    Array { element: TypePath, len: usize },
+    /// A borrowed slice, represented as a [`c_slice::Ref`], which is a pointer to the first
+    /// element followed by the number of elements
+    Slice { element: TypePath },
+    /// An owned, fixed-size buffer, represented as a [`ScriptVec`][crate::ScriptVec]
+    Vec { element: TypePath },
+    /// A function that can be called by any adapter, represented as a
+    /// [`CallbackHandle`][crate::CallbackHandle]
+    Callback(FunctionDefinition),
     /// A primitive type
     Primitive(Primitive),
 }
 
+/// A field of a struct
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StructField {
+    /// The name of the field, or its index for tuple structs
+    pub name: String,
+    /// The type of the field
+    pub type_path: TypePath,
+    /// The offset of the field from the start of the struct in bytes
+    pub offset: usize,
+    /// The size and alignment of the field
+    pub layout: DataLayout,
+}
+
+impl StructField {
+    /// Get a pointer to this field in an instance of the struct
+    ///
+    /// The resulting pointer may be used to read the field with the layout of its `type_path`,
+    /// as long as `instance` points to a valid instance of the struct.
+    pub fn ptr_in(&self, instance: *const Void) -> *const Void {
+        (instance as *const u8).wrapping_add(self.offset) as *const Void
+    }
+
+    /// Get a mutable pointer to this field in an instance of the struct
+    ///
+    /// The resulting pointer may be used to write to the field with the layout of its
+    /// `type_path`, as long as `instance` points to a valid, mutable instance of the struct.
+    pub fn ptr_in_mut(&self, instance: *mut Void) -> *mut Void {
+        (instance as *mut u8).wrapping_add(self.offset) as *mut Void
+    }
+}
+
 /// A primitive type
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Primitive {
     U8,
     U16,
@@ -120,37 +479,362 @@ pub enum Primitive {
     I128,
     F32,
     F64,
+    /// A Unicode scalar value, which takes up 4 bytes like a Rust `char`
     Char,
     Bool,
+    /// The unit type `()`, which takes up no space
+    Unit,
+    /// A borrowed UTF-8 string represented as a [`str::Ref`]
+    Str,
+    /// An owned UTF-8 string represented as a [`repr_c::String`]
+    String,
+    /// An unsigned pointer-sized integer
+    Usize,
+    /// A signed pointer-sized integer
+    Isize,
+    /// An untyped pointer
+    Pointer,
+    /// A C `char`, which may be signed or unsigned depending on the platform
+    CChar,
 }
 
 impl HasDataLayout for Primitive {
+    /// Get the layout of the primitive for the platform that Dynamite was compiled for
     #[rustfmt::skip]
     fn get_data_layout(&self) -> DataLayout {
         match self {
-            Primitive::Char => DataLayout::from_size_align(1, 1).unwrap(),
-            Primitive::Bool => DataLayout::from_size_align(1, 1).unwrap(),
-            Primitive::U8   => DataLayout::from_size_align(1, 1).unwrap(),
-            Primitive::U16  => DataLayout::from_size_align(2, 2).unwrap(),
-            Primitive::U32  => DataLayout::from_size_align(4, 4).unwrap(),
-            Primitive::U64  => DataLayout::from_size_align(8, 8).unwrap(),
-            Primitive::U128 => DataLayout::from_size_align(16, 16).unwrap(),
-            Primitive::I8   => DataLayout::from_size_align(1, 1).unwrap(),
-            Primitive::I16  => DataLayout::from_size_align(2, 2).unwrap(),
-            Primitive::I32  => DataLayout::from_size_align(4, 4).unwrap(),
-            Primitive::I64  => DataLayout::from_size_align(8, 8).unwrap(),
-            Primitive::I128 => DataLayout::from_size_align(16, 16).unwrap(),
-            Primitive::F32  => DataLayout::from_size_align(4, 4).unwrap(),
-            Primitive::F64  => DataLayout::from_size_align(8, 8).unwrap(),
+            Primitive::Char    => DataLayout::of::<char>(),
+            Primitive::Bool    => DataLayout::of::<bool>(),
+            Primitive::U8      => DataLayout::of::<u8>(),
+            Primitive::U16     => DataLayout::of::<u16>(),
+            Primitive::U32     => DataLayout::of::<u32>(),
+            Primitive::U64     => DataLayout::of::<u64>(),
+            Primitive::U128    => DataLayout::of::<u128>(),
+            Primitive::I8      => DataLayout::of::<i8>(),
+            Primitive::I16     => DataLayout::of::<i16>(),
+            Primitive::I32     => DataLayout::of::<i32>(),
+            Primitive::I64     => DataLayout::of::<i64>(),
+            Primitive::I128    => DataLayout::of::<i128>(),
+            Primitive::F32     => DataLayout::of::<f32>(),
+            Primitive::F64     => DataLayout::of::<f64>(),
+            Primitive::Unit    => DataLayout::of::<()>(),
+            Primitive::Str     => DataLayout::of::<str::Ref<'static>>(),
+            Primitive::String  => DataLayout::of::<repr_c::String>(),
+            Primitive::Usize   => DataLayout::of::<usize>(),
+            Primitive::Isize   => DataLayout::of::<isize>(),
+            Primitive::Pointer => DataLayout::of::<*const Void>(),
+            Primitive::CChar   => DataLayout::of::<std::os::raw::c_char>(),
         }
     }
 }
 
+/// The definition of a method associated to a struct
+///
+/// Methods are also added to the [`ScriptApi`] as plain functions under the path
+/// `[struct_path]::[method_name]`, which is the path used to call them. Methods that take `self`
+/// get the pointer to the struct instance as their first argument.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MethodDefinition {
+    /// The name of the method
+    pub name: String,
+    /// How the method takes `self`
+    pub receiver: Receiver,
+    /// The definition of the function that implements the method, including the `self` argument
+    pub function: FunctionDefinition,
+}
+
+/// How a method takes its `self` argument
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Receiver {
+    /// The method doesn't take `self`, such as a constructor
+    Static,
+    /// The method takes `&self`
+    Shared,
+    /// The method takes `&mut self`, and may modify the instance passed to it
+    Mutable,
+    /// The method takes `self` by value. The instance passed to it is cloned, so the caller keeps
+    /// ownership of it.
+    Value,
+}
+
 /// the definition for a script type's method
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct FunctionDefinition {
-    /// The arguments of the functions, mapping the arg name to the type path
-    pub arguments: HashMap<Cow<'static, str>, TypePath>,
+    /// The arguments of the function, in the order that they must be passed to the function
+    pub arguments: Vec<FunctionArgument>,
     /// The return value of the function
     pub return_type: Option<TypePath>,
+    /// Who owns the value returned by the function
+    #[serde(default)]
+    pub return_ownership: ReturnOwnership,
+    /// Whether the function may fail with a [`CallError::Script`][crate::CallError::Script]
+    /// error, which language adapters should raise as an exception or error value in the script
+    #[serde(default)]
+    pub fallible: bool,
+    /// Whether the function may return a null pointer to indicate that there is no value, even
+    /// though it has a `return_type`
+    #[serde(default)]
+    pub optional_return: bool,
+}
+
+/// The ownership of a pointer returned from a function
+///
+/// Language adapters may use different allocators, so a value must always be released by the
+/// adapter that allocated it. This describes whether, and how, the caller must release the value.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReturnOwnership {
+    /// The returned pointer borrows from the function's arguments and is only valid for as long as
+    /// the arguments are. The caller must not free it. This is also used for functions that don't
+    /// return a value.
+    #[default]
+    Borrowed,
+    /// The returned value is owned by the callee and stays valid for as long as the callee's
+    /// adapter is loaded, such as a pointer to a static. The caller must not free it.
+    OwnedByCallee,
+    /// Ownership of the returned value is transferred to the caller, which must release it with
+    /// [`HostFunctions::free_value`] once it is done with it. This gives the value back to the
+    /// adapter that provides the function so that it can be dropped with the right allocator.
+    ///
+    /// [`HostFunctions::free_value`]: crate::HostFunctions::free_value
+    OwnedByCaller,
+}
+
+/// An argument to a function
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FunctionArgument {
+    /// The name of the argument
+    pub name: Cow<'static, str>,
+    /// The type of the argument
+    pub type_path: TypePath,
+    /// The value that script adapters should pass for this argument if the script omits it
+    pub default_value: Option<LiteralValue>,
+    /// Documentation for the argument
+    pub doc: Option<Cow<'static, str>>,
+}
+
+impl FunctionArgument {
+    /// Create an argument with the given name and type and no default value or documentation
+    pub fn new<N: Into<Cow<'static, str>>, T: Into<TypePath>>(name: N, type_path: T) -> Self {
+        Self {
+            name: name.into(),
+            type_path: type_path.into(),
+            default_value: None,
+            doc: None,
+        }
+    }
+}
+
+/// A constant value that can be embedded in a [`ScriptApi`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum LiteralValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Char(char),
+    String(String),
+}
+
+macro_rules! impl_literal_from {
+    ($variant:ident, $as:ty, $($ty:ty),*) => {
+        $(
+            impl From<$ty> for LiteralValue {
+                #[allow(clippy::unnecessary_cast)]
+                fn from(value: $ty) -> Self {
+                    LiteralValue::$variant(value as $as)
+                }
+            }
+        )*
+    };
+}
+
+impl_literal_from!(Int, i64, i8, i16, i32, i64, isize);
+impl_literal_from!(UInt, u64, u8, u16, u32, u64, usize);
+impl_literal_from!(Float, f64, f32, f64);
+
+impl From<bool> for LiteralValue {
+    fn from(value: bool) -> Self {
+        LiteralValue::Bool(value)
+    }
+}
+
+impl From<char> for LiteralValue {
+    fn from(value: char) -> Self {
+        LiteralValue::Char(value)
+    }
+}
+
+impl From<&str> for LiteralValue {
+    fn from(value: &str) -> Self {
+        LiteralValue::String(value.into())
+    }
+}
+
+impl From<String> for LiteralValue {
+    fn from(value: String) -> Self {
+        LiteralValue::String(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api(types: &[(&str, Primitive)]) -> ScriptApi {
+        types
+            .iter()
+            .map(|(path, primitive)| ((*path).into(), ScriptType::Primitive(primitive.clone())))
+            .collect()
+    }
+
+    fn field<T>(name: &str, offset: usize) -> StructField {
+        StructField {
+            name: name.into(),
+            type_path: "test::Field".into(),
+            offset,
+            layout: DataLayout::of::<T>(),
+        }
+    }
+
+    fn struct_definition(size: usize, align: usize, fields: Vec<StructField>) -> StructDefinition {
+        StructDefinition {
+            layout: DataLayout::from_size_align(size, align).unwrap(),
+            component_type: DataType::Struct { fields },
+            method_definitions: vec![],
+        }
+    }
+
+    #[test]
+    fn struct_layouts_are_validated() {
+        let valid = struct_definition(8, 4, vec![field::<u32>("b", 4), field::<u16>("a", 0)]);
+        assert!(valid.validate_layout().is_ok());
+
+        let unpadded = struct_definition(6, 4, vec![field::<u16>("a", 0)]);
+        assert!(matches!(
+            unpadded.validate_layout(),
+            Err(LayoutError::SizeNotPadded { size: 6, align: 4 })
+        ));
+
+        let misaligned = struct_definition(8, 4, vec![field::<u32>("a", 2)]);
+        assert!(matches!(
+            misaligned.validate_layout(),
+            Err(LayoutError::FieldMisaligned { .. })
+        ));
+
+        let out_of_bounds = struct_definition(4, 4, vec![field::<u64>("a", 0)]);
+        assert!(matches!(
+            out_of_bounds.validate_layout(),
+            Err(LayoutError::FieldOutOfBounds {
+                end: 8,
+                size: 4,
+                ..
+            })
+        ));
+
+        let overflowing = struct_definition(8, 4, vec![field::<u32>("a", usize::MAX - 3)]);
+        assert!(matches!(
+            overflowing.validate_layout(),
+            Err(LayoutError::FieldOverflow { .. })
+        ));
+
+        let overlapping = struct_definition(8, 4, vec![field::<u32>("a", 0), field::<u16>("b", 2)]);
+        assert!(matches!(
+            overlapping.validate_layout(),
+            Err(LayoutError::FieldsOverlap { .. })
+        ));
+    }
+
+    #[test]
+    fn deserialized_layouts_are_validated() {
+        let layout = |size, align| serde_cbor::to_vec(&DataLayout { size, align }).unwrap();
+
+        assert_eq!(
+            serde_cbor::from_slice::<DataLayout>(&layout(8, 4)).unwrap(),
+            DataLayout::from_size_align(8, 4).unwrap()
+        );
+        assert!(serde_cbor::from_slice::<DataLayout>(&layout(8, 0)).is_err());
+        assert!(serde_cbor::from_slice::<DataLayout>(&layout(8, 3)).is_err());
+    }
+
+    fn enum_definition(tag_type: &str, fields: Vec<StructField>) -> EnumDefinition {
+        EnumDefinition {
+            layout: DataLayout::from_size_align(8, 4).unwrap(),
+            tag_type: tag_type.into(),
+            variants: vec![
+                EnumVariant {
+                    name: "Empty".into(),
+                    discriminant: 0,
+                    fields: vec![],
+                },
+                EnumVariant {
+                    name: "Full".into(),
+                    discriminant: 1,
+                    fields,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn enum_layouts_are_validated() {
+        let valid = enum_definition("std::u8", vec![field::<u8>("a", 1), field::<u16>("b", 2)]);
+        assert!(valid.validate_layout().is_ok());
+        assert!(enum_definition("std::i32", vec![field::<u32>("a", 4)])
+            .validate_layout()
+            .is_ok());
+
+        // Fields may not overlap the tag
+        assert!(matches!(
+            enum_definition("std::u16", vec![field::<u8>("a", 1)]).validate_layout(),
+            Err(LayoutError::FieldsOverlap { first, second }) if first == "tag" && second == "Full::a"
+        ));
+        assert!(matches!(
+            enum_definition("std::u8", vec![field::<u32>("a", 6)]).validate_layout(),
+            Err(LayoutError::FieldMisaligned { field, .. }) if field == "Full::a"
+        ));
+        let mut too_small = enum_definition("std::u64", vec![]);
+        too_small.layout = DataLayout::from_size_align(4, 4).unwrap();
+        assert!(matches!(
+            too_small.validate_layout(),
+            Err(LayoutError::FieldOutOfBounds { field, .. }) if field == "tag"
+        ));
+        assert!(matches!(
+            enum_definition("std::f32", vec![]).validate_layout(),
+            Err(LayoutError::InvalidTagType(_))
+        ));
+    }
+
+    #[test]
+    fn api_diffs_list_sorted_changes() {
+        let old = api(&[
+            ("game::b", Primitive::U8),
+            ("game::a", Primitive::U8),
+            ("game::kept", Primitive::U8),
+            ("game::changed", Primitive::U8),
+        ]);
+        let new = api(&[
+            ("game::kept", Primitive::U8),
+            ("game::changed", Primitive::U16),
+            ("game::d", Primitive::U8),
+            ("game::c", Primitive::U8),
+        ]);
+
+        assert_eq!(
+            ApiDiff::new(&old, &new),
+            ApiDiff {
+                added: vec!["game::c".into(), "game::d".into()],
+                removed: vec!["game::a".into(), "game::b".into()],
+                changed: vec!["game::changed".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn api_diffs_of_equal_apis_are_empty() {
+        let api = api(&[("game::a", Primitive::U8)]);
+
+        assert!(ApiDiff::new(&api, &api).is_empty());
+        assert!(ApiDiff::new(&ScriptApi::new(), &ScriptApi::new()).is_empty());
+        assert!(!ApiDiff::new(&ScriptApi::new(), &api).is_empty());
+    }
 }
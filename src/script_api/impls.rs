@@ -8,7 +8,7 @@ macro_rules! impl_primitive_type {
             }
 
             fn script_path() -> TypePath {
-                stringify!($prim).into()
+                concat!("std::", stringify!($prim)).into()
             }
         }
     };
@@ -23,4 +23,113 @@ impl_primitive_type!(i8, I8);
 impl_primitive_type!(i16, I16);
 impl_primitive_type!(i32, I32);
 impl_primitive_type!(i64, I64);
-impl_primitive_type!(i128, I128);
\ No newline at end of file
+impl_primitive_type!(i128, I128);
+impl_primitive_type!(f32, F32);
+impl_primitive_type!(f64, F64);
+impl_primitive_type!(char, Char);
+impl_primitive_type!(bool, Bool);
+impl_primitive_type!(usize, Usize);
+impl_primitive_type!(isize, Isize);
+
+impl HasScriptType for () {
+    fn script_type() -> ScriptType {
+        ScriptType::Primitive(Primitive::Unit)
+    }
+
+    fn script_path() -> TypePath {
+        "std::unit".into()
+    }
+}
+
+impl<T> HasScriptType for *const T {
+    fn script_type() -> ScriptType {
+        ScriptType::Primitive(Primitive::Pointer)
+    }
+
+    fn script_path() -> TypePath {
+        "std::pointer".into()
+    }
+}
+
+impl<T> HasScriptType for *mut T {
+    fn script_type() -> ScriptType {
+        ScriptType::Primitive(Primitive::Pointer)
+    }
+
+    fn script_path() -> TypePath {
+        "std::pointer".into()
+    }
+}
+
+impl HasScriptType for str {
+    fn script_type() -> ScriptType {
+        ScriptType::Primitive(Primitive::Str)
+    }
+
+    fn script_path() -> TypePath {
+        "std::str".into()
+    }
+}
+
+impl HasScriptType for str::Ref<'_> {
+    fn script_type() -> ScriptType {
+        ScriptType::Primitive(Primitive::Str)
+    }
+
+    fn script_path() -> TypePath {
+        "std::str".into()
+    }
+}
+
+impl HasScriptType for repr_c::String {
+    fn script_type() -> ScriptType {
+        ScriptType::Primitive(Primitive::String)
+    }
+
+    fn script_path() -> TypePath {
+        "std::String".into()
+    }
+}
+
+impl<T: HasScriptType, const N: usize> HasScriptType for [T; N] {
+    fn script_type() -> ScriptType {
+        ScriptType::Struct(StructDefinition {
+            layout: DataLayout::of::<Self>(),
+            component_type: DataType::Array {
+                element: T::script_path(),
+                len: N,
+            },
+            method_definitions: vec![],
+        })
+    }
+
+    fn script_path() -> TypePath {
+        format!("std::Array<{}, {}>", T::script_path(), N).into()
+    }
+}
+
+impl<T: HasScriptType> HasScriptType for c_slice::Ref<'_, T> {
+    fn script_type() -> ScriptType {
+        ScriptType::Struct(StructDefinition {
+            layout: DataLayout::of::<Self>(),
+            component_type: DataType::Slice {
+                element: T::script_path(),
+            },
+            method_definitions: vec![],
+        })
+    }
+
+    fn script_path() -> TypePath {
+        format!("std::Slice<{}>", T::script_path()).into()
+    }
+}
+
+impl<T: HasScriptType> HasScriptType for c_slice::Mut<'_, T> {
+    fn script_type() -> ScriptType {
+        <c_slice::Ref<'_, T>>::script_type()
+    }
+
+    fn script_path() -> TypePath {
+        <c_slice::Ref<'_, T>>::script_path()
+    }
+}
@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+
+use std::{borrow::Borrow, fmt, ops::Deref, str::FromStr};
+
+use crate::TypePathError;
+
+/// The path to a scripted type, i.e. the "module" path such as "mygame::physics::RigidBody".
+///
+/// A path is made of segments separated by `::`. Each segment is an identifier, optionally
+/// followed by generic arguments in angle brackets, such as the `Vec<std::f32>` in
+/// `std::Vec<std::f32>`. Separators inside of brackets don't split segments.
+///
+/// Converting a path from a string with [`From`] doesn't validate it, so that paths can be created
+/// cheaply in generated code. Use [`TypePath::new`] or [`str::parse`] to validate a path, or
+/// [`TypePath::validate`] to check an existing one.
+///
+/// # Example
+///
+/// ```
+/// # use dynamite::TypePath;
+/// let path: TypePath = "mygame::physics::RigidBody".parse().unwrap();
+///
+/// assert_eq!(path.segments().collect::<Vec<_>>(), ["mygame", "physics", "RigidBody"]);
+/// assert_eq!(path.name(), "RigidBody");
+/// assert_eq!(path.parent().unwrap(), "mygame::physics");
+/// assert_eq!(path.parent().unwrap().join("Collider"), "mygame::physics::Collider");
+/// assert!(path.is_within(&"mygame".into()));
+/// assert!(!path.is_within(&"my".into()));
+///
+/// let callback: TypePath = "std::Callback<fn(std::f32) -> std::bool>".parse().unwrap();
+/// assert_eq!(callback.name(), "Callback<fn(std::f32) -> std::bool>");
+///
+/// assert!("mygame::".parse::<TypePath>().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[serde(transparent)]
+pub struct TypePath(String);
+
+/// The separator between the segments of a [`TypePath`]
+const SEPARATOR: &str = "::";
+
+impl TypePath {
+    /// Create a validated type path
+    pub fn new<S: Into<String>>(path: S) -> Result<Self, TypePathError> {
+        let path = Self(path.into());
+        path.validate()?;
+
+        Ok(path)
+    }
+
+    /// Get the path as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Make sure that the path is made of valid segments
+    pub fn validate(&self) -> Result<(), TypePathError> {
+        if self.0.is_empty() {
+            return Err(TypePathError::Empty);
+        }
+
+        let mut depth = 0usize;
+        let mut previous = None;
+        for c in self.0.chars() {
+            match c {
+                '<' | '(' => depth += 1,
+                // The `>` in the `->` of function types is not a bracket
+                '>' if previous == Some('-') => (),
+                '>' | ')' => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or_else(|| TypePathError::UnbalancedBrackets(self.clone()))?
+                }
+                _ => (),
+            }
+            previous = Some(c);
+        }
+        if depth != 0 {
+            return Err(TypePathError::UnbalancedBrackets(self.clone()));
+        }
+
+        for segment in self.segments() {
+            // The name of the segment comes before any generic arguments
+            let name = segment.split('<').next().unwrap_or_default();
+            let mut chars = name.chars();
+            let valid_start = chars
+                .next()
+                .map(|c| c.is_alphabetic() || c == '_')
+                .unwrap_or(false);
+            if !valid_start || !chars.all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(TypePathError::InvalidSegment {
+                    path: self.clone(),
+                    segment: segment.into(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over the `::` separated segments of the path
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        let path = self.as_str();
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut segments = Vec::new();
+
+        let bytes = path.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'<' | b'(' => depth += 1,
+                b'>' if i > 0 && bytes[i - 1] == b'-' => (),
+                b'>' | b')' => depth = depth.saturating_sub(1),
+                b':' if depth == 0 && path[i..].starts_with(SEPARATOR) => {
+                    segments.push(&path[start..i]);
+                    i += SEPARATOR.len();
+                    start = i;
+                    continue;
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+        segments.push(&path[start..]);
+
+        segments.into_iter()
+    }
+
+    /// Get the last segment of the path
+    pub fn name(&self) -> &str {
+        self.segments().last().unwrap_or_default()
+    }
+
+    /// Get the path without its last segment, or `None` if the path only has one segment
+    pub fn parent(&self) -> Option<TypePath> {
+        let name_len = self.name().len();
+        if name_len == self.0.len() {
+            None
+        } else {
+            Some(Self(
+                self.0[..self.0.len() - name_len - SEPARATOR.len()].into(),
+            ))
+        }
+    }
+
+    /// Create a new path by adding a segment, or a `::` separated path, to the end of this one
+    pub fn join(&self, path: &str) -> TypePath {
+        Self(format!("{}{}{}", self.0, SEPARATOR, path))
+    }
+
+    /// Whether this path is equal to or inside of the `root` path
+    ///
+    /// Only whole segments are compared, so `mygame::physics` is within `mygame` but not within
+    /// `my`.
+    pub fn is_within(&self, root: &TypePath) -> bool {
+        let mut segments = self.segments();
+        root.segments()
+            .all(|root_segment| segments.next() == Some(root_segment))
+    }
+}
+
+impl From<&str> for TypePath {
+    fn from(path: &str) -> Self {
+        Self(path.into())
+    }
+}
+
+impl From<String> for TypePath {
+    fn from(path: String) -> Self {
+        Self(path)
+    }
+}
+
+impl From<TypePath> for String {
+    fn from(path: TypePath) -> Self {
+        path.0
+    }
+}
+
+impl FromStr for TypePath {
+    type Err = TypePathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::new(path)
+    }
+}
+
+impl Deref for TypePath {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for TypePath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for TypePath {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for TypePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for TypePath {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for TypePath {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_paths() {
+        for path in &[
+            "mygame",
+            "mygame::physics::RigidBody",
+            "_private::Type2",
+            "std::Vec<std::f32>",
+            "std::Array<std::Vec<std::u8>, 4>",
+            "std::Callback<fn(std::f32, std::u32) -> std::bool>",
+        ] {
+            assert!(TypePath::new(*path).is_ok(), "{} should be valid", path);
+        }
+    }
+
+    #[test]
+    fn invalid_paths() {
+        assert!(matches!(TypePath::new(""), Err(TypePathError::Empty)));
+        for path in &[
+            "mygame::",
+            "::mygame",
+            "mygame::::physics",
+            "my game",
+            "2d::Point",
+        ] {
+            assert!(
+                matches!(
+                    TypePath::new(*path),
+                    Err(TypePathError::InvalidSegment { .. })
+                ),
+                "{} should have an invalid segment",
+                path
+            );
+        }
+        for path in &[
+            "std::Vec<std::f32",
+            "std::Vec>",
+            "std::Callback<fn(std::f32 -> std::bool>",
+        ] {
+            assert!(
+                matches!(
+                    TypePath::new(*path),
+                    Err(TypePathError::UnbalancedBrackets(_))
+                ),
+                "{} should have unbalanced brackets",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn separators_in_brackets_do_not_split_segments() {
+        let path = TypePath::from("std::Map<std::String, mygame::Item>::Entry");
+
+        assert_eq!(
+            path.segments().collect::<Vec<_>>(),
+            ["std", "Map<std::String, mygame::Item>", "Entry"]
+        );
+        assert_eq!(path.name(), "Entry");
+        assert_eq!(
+            path.parent().unwrap(),
+            "std::Map<std::String, mygame::Item>"
+        );
+        assert!(TypePath::from("mygame").parent().is_none());
+    }
+
+    #[test]
+    fn is_within_compares_whole_segments() {
+        let path = TypePath::from("mygame::physics::RigidBody");
+
+        assert!(path.is_within(&"mygame".into()));
+        assert!(path.is_within(&"mygame::physics".into()));
+        assert!(path.is_within(&path));
+        assert!(!path.is_within(&"my".into()));
+        assert!(!path.is_within(&"mygame::phys".into()));
+        assert!(!path.is_within(&"mygame::physics::RigidBody::Handle".into()));
+        assert!(!TypePath::from("std::Vec<std::f32>").is_within(&"std::Vec".into()));
+    }
+}
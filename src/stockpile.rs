@@ -1,18 +1,34 @@
 use std::collections::HashMap;
 
-use crate::{LanguageAdapter, ScriptApi, ScriptApiError, ScriptType, TypePath, Void};
+use crate::{
+    CallError, LanguageAdapter, MethodDefinition, ScriptApi, ScriptApiError, ScriptType, TypePath,
+    Void,
+};
+
+/// The signature of the proxy functions used to call stockpile functions
+///
+/// The arguments are pointers to the values of the function's arguments, and errors returned by
+/// fallible functions are returned as [`CallError::Script`] errors.
+pub type StockpileFunction = unsafe fn(args: &[*const Void]) -> Result<*const Void, CallError>;
 
 /// A [`LanguageAdapter`] that uses the [`inventory`] crate to pull in API elements from the entire
 /// crate graph.
 pub struct Stockpile {
     api: ScriptApi,
-    function_pointers: HashMap<TypePath, unsafe fn(args: &[*const Void]) -> *const Void>,
+    /// The function pointers, indexed by the slots in `function_slots`
+    function_pointers: Vec<StockpileFunction>,
+    function_slots: HashMap<TypePath, usize>,
+    free_pointers: HashMap<TypePath, unsafe fn(value: *const Void)>,
+    globals: HashMap<TypePath, StockpileGlobal>,
 }
 
 impl Stockpile {
     pub fn new() -> Result<Self, ScriptApiError> {
         let mut api = ScriptApi::new();
-        let mut function_pointers = HashMap::new();
+        let mut function_pointers = Vec::new();
+        let mut function_slots = HashMap::new();
+        let mut free_pointers = HashMap::new();
+        let mut globals = HashMap::new();
 
         // Loop through items in the stockpile and add them to the API
         for item in inventory::iter::<StockpileItem> {
@@ -26,13 +42,65 @@ impl Stockpile {
 
             // Register the function pointer if present
             if let Some(pointer) = item.function_pointer {
-                function_pointers.insert(item.path.clone(), pointer);
+                function_slots.insert(item.path.clone(), function_pointers.len());
+                function_pointers.push(pointer);
+            }
+
+            // Register the free function pointer if present
+            if let Some(pointer) = item.free_pointer {
+                free_pointers.insert(item.path.clone(), pointer);
             }
         }
 
+        // Add methods to the structs that they are implemented on
+        for method in inventory::iter::<StockpileMethod> {
+            let path = method.self_path.join(&method.method.name);
+
+            match api.get_mut(&method.self_path) {
+                Some(ScriptType::Struct(definition)) => {
+                    definition.method_definitions.push(method.method.clone())
+                }
+                Some(_) => return Err(ScriptApiError::NotAStruct(method.self_path.clone())),
+                None => return Err(ScriptApiError::NotFound(method.self_path.clone())),
+            }
+
+            // Register the method as a function so that it can be called like any other function
+            if api
+                .insert(
+                    path.clone(),
+                    ScriptType::Function(method.method.function.clone()),
+                )
+                .is_some()
+            {
+                return Err(ScriptApiError::TypeRedefined(path));
+            }
+
+            function_slots.insert(path.clone(), function_pointers.len());
+            function_pointers.push(method.function_pointer);
+            if let Some(pointer) = method.free_pointer {
+                free_pointers.insert(path, pointer);
+            }
+        }
+
+        // Add globals
+        for global in inventory::iter::<StockpileGlobal> {
+            let script_type = ScriptType::Global {
+                type_path: global.type_path.clone(),
+                settable: global.set_pointer.is_some(),
+            };
+            if api.insert(global.path.clone(), script_type).is_some() {
+                return Err(ScriptApiError::TypeRedefined(global.path.clone()));
+            }
+
+            globals.insert(global.path.clone(), global.clone());
+        }
+
         Ok(Self {
             api,
             function_pointers,
+            function_slots,
+            free_pointers,
+            globals,
         })
     }
 }
@@ -47,13 +115,85 @@ impl LanguageAdapter for Stockpile {
         _host_functions: &dyn crate::HostFunctions,
         path: &str,
         args: &[*const crate::Void],
-    ) -> *const crate::Void {
-        if let Some(function_pointer) = self.function_pointers.get(path) {
-            (function_pointer)(args)
+    ) -> Result<*const crate::Void, CallError> {
+        if let Some(&slot) = self.function_slots.get(path) {
+            (self.function_pointers[slot])(args)
         } else {
-            panic!("Call to non-existent function");
+            Err(CallError::NotFound(path.into()))
         }
     }
+
+    fn resolve_function(
+        &self,
+        _host_functions: &dyn crate::HostFunctions,
+        path: &str,
+    ) -> Result<Option<u64>, CallError> {
+        self.function_slots
+            .get(path)
+            .map(|&slot| Some(slot as u64))
+            .ok_or_else(|| CallError::NotFound(path.into()))
+    }
+
+    unsafe fn call_slot(
+        &self,
+        _host_functions: &dyn crate::HostFunctions,
+        slot: u64,
+        args: &[*const crate::Void],
+    ) -> Result<*const crate::Void, CallError> {
+        let function_pointer = self.function_pointers.get(slot as usize).ok_or_else(|| {
+            CallError::AdapterError(format!("Stockpile has no function slot {}", slot))
+        })?;
+
+        (function_pointer)(args)
+    }
+
+    unsafe fn free_value(
+        &self,
+        _host_functions: &dyn crate::HostFunctions,
+        path: &str,
+        value: *const Void,
+    ) -> Result<(), CallError> {
+        if let Some(free_pointer) = self.free_pointers.get(path) {
+            (free_pointer)(value);
+            Ok(())
+        } else if self.function_slots.contains_key(path) {
+            Err(CallError::NotOwnedByCaller(path.into()))
+        } else {
+            Err(CallError::NotFound(path.into()))
+        }
+    }
+
+    unsafe fn get_global(
+        &self,
+        _host_functions: &dyn crate::HostFunctions,
+        path: &str,
+        out: *mut Void,
+    ) -> Result<(), CallError> {
+        let global = self
+            .globals
+            .get(path)
+            .ok_or_else(|| CallError::NotFound(path.into()))?;
+        (global.get_pointer)(out);
+
+        Ok(())
+    }
+
+    unsafe fn set_global(
+        &self,
+        _host_functions: &dyn crate::HostFunctions,
+        path: &str,
+        value: *const Void,
+    ) -> Result<(), CallError> {
+        let set_pointer = self
+            .globals
+            .get(path)
+            .ok_or_else(|| CallError::NotFound(path.into()))?
+            .set_pointer
+            .ok_or_else(|| CallError::GlobalNotSettable(path.into()))?;
+        (set_pointer)(value);
+
+        Ok(())
+    }
 }
 
 /// An item in the Dynamite stockpile
@@ -64,11 +204,49 @@ pub struct StockpileItem {
     /// The script type of the item
     pub script_type: ScriptType,
     /// A function pointer to register with this type, if it's a function
-    pub function_pointer: Option<unsafe fn(args: &[*const Void]) -> *const Void>,
+    pub function_pointer: Option<StockpileFunction>,
+    /// A function pointer used to free the values returned by the function, if it returns
+    /// [`OwnedByCaller`][crate::ReturnOwnership::OwnedByCaller] values
+    pub free_pointer: Option<unsafe fn(value: *const Void)>,
 }
 
 inventory::collect!(StockpileItem);
 
+/// A method in the Dynamite stockpile
+///
+/// Methods are added to the [`method_definitions`][crate::StructDefinition::method_definitions]
+/// of the struct at `self_path`, which must also be in the stockpile, and are callable at the
+/// path `[self_path]::[method_name]`.
+#[derive(Clone)]
+pub struct StockpileMethod {
+    /// The path of the struct that the method is implemented on
+    pub self_path: TypePath,
+    /// The definition of the method
+    pub method: MethodDefinition,
+    /// A function pointer used to call the method
+    pub function_pointer: StockpileFunction,
+    /// A function pointer used to free the values returned by the method, if it returns
+    /// [`OwnedByCaller`][crate::ReturnOwnership::OwnedByCaller] values
+    pub free_pointer: Option<unsafe fn(value: *const Void)>,
+}
+
+inventory::collect!(StockpileMethod);
+
+/// A global variable in the Dynamite stockpile
+#[derive(Clone)]
+pub struct StockpileGlobal {
+    /// The path of the global
+    pub path: TypePath,
+    /// The type of the global
+    pub type_path: TypePath,
+    /// A function pointer that writes a copy of the global's value to the given pointer
+    pub get_pointer: unsafe fn(out: *mut Void),
+    /// A function pointer that sets the global to a copy of the given value, if it is settable
+    pub set_pointer: Option<unsafe fn(value: *const Void)>,
+}
+
+inventory::collect!(StockpileGlobal);
+
 #[macro_export]
 macro_rules! add_binding {
     ($item:expr) => {
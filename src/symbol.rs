@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+
+use crate::TypePath;
+
+/// A compact id for a [`TypePath`] in the scripting API
+///
+/// Every path in the scripting API is interned when its adapter is added to the Dynamite host.
+/// Resolving a path to its symbol with
+/// [`HostFunctions::resolve_symbol`][crate::HostFunctions::resolve_symbol] only has to be done
+/// once, after which values and globals are accessed by symbol without hashing or copying the
+/// path. Adapters call functions by symbol through a [`FunctionHandle`], which also binds the
+/// adapter that provides the function.
+#[repr(C)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId {
+    /// The index of the symbol in the host
+    pub id: u32,
+}
+
+impl SymbolId {
+    /// Get the index of the symbol
+    pub(crate) fn index(self) -> usize {
+        self.id as usize
+    }
+}
+
+/// The interned paths of a Dynamite host
+#[derive(Default)]
+pub(crate) struct SymbolTable {
+    paths: Vec<TypePath>,
+    ids: HashMap<TypePath, SymbolId>,
+}
+
+impl SymbolTable {
+    /// Get the symbol of a path, adding it to the table if it isn't there yet
+    pub(crate) fn intern(&mut self, path: &TypePath) -> SymbolId {
+        if let Some(&symbol) = self.ids.get(path) {
+            return symbol;
+        }
+
+        let symbol = SymbolId {
+            id: self.paths.len() as u32,
+        };
+        self.paths.push(path.clone());
+        self.ids.insert(path.clone(), symbol);
+
+        symbol
+    }
+
+    /// Get the symbol of an interned path
+    pub(crate) fn get(&self, path: &str) -> Option<SymbolId> {
+        self.ids.get(path).copied()
+    }
+
+    /// Get the path of a symbol
+    pub(crate) fn path(&self, symbol: SymbolId) -> Option<&TypePath> {
+        self.paths.get(symbol.index())
+    }
+
+    /// Iterate over the interned paths in the order of their symbols
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &TypePath> {
+        self.paths.iter()
+    }
+}
+
+/// A function in the scripting API that has been resolved ahead of time
+///
+/// A handle is created with
+/// [`HostFunctions::resolve_function`][crate::HostFunctions::resolve_function] and binds the
+/// function's symbol to the adapter that provides it and to the adapter's own slot for the
+/// function, if it has one. Calls made with
+/// [`HostFunctions::call_handle`][crate::HostFunctions::call_handle] skip every path lookup, both
+/// in the host and in the adapter.
+#[repr(C)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FunctionHandle {
+    symbol: SymbolId,
+    adapter: u32,
+    generation: u32,
+    slot: u64,
+    has_slot: bool,
+}
+
+impl FunctionHandle {
+    /// Create a handle to a function provided by the adapter at the given index
+    pub(crate) fn new(
+        symbol: SymbolId,
+        adapter: usize,
+        generation: u32,
+        slot: Option<u64>,
+    ) -> Self {
+        Self {
+            symbol,
+            adapter: adapter as u32,
+            generation,
+            slot: slot.unwrap_or_default(),
+            has_slot: slot.is_some(),
+        }
+    }
+
+    /// Get the symbol of the function
+    pub fn symbol(&self) -> SymbolId {
+        self.symbol
+    }
+
+    /// Get the index of the adapter that provides the function
+    pub(crate) fn adapter(&self) -> usize {
+        self.adapter as usize
+    }
+
+    /// Get the generation of the adapter instance that the handle was resolved with
+    pub(crate) fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Get the adapter's slot for the function, or `None` if the adapter doesn't use slots
+    pub fn slot(&self) -> Option<u64> {
+        Some(self.slot).filter(|_| self.has_slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_interned_once() {
+        let mut symbols = SymbolTable::default();
+        let first = symbols.intern(&"game::run".into());
+        let second = symbols.intern(&"game::stop".into());
+
+        assert_ne!(first, second);
+        assert_eq!(symbols.intern(&"game::run".into()), first);
+        assert_eq!(symbols.get("game::stop"), Some(second));
+        assert_eq!(symbols.get("game::jump"), None);
+        assert_eq!(symbols.path(first).unwrap(), "game::run");
+        assert_eq!(
+            symbols.paths().collect::<Vec<_>>(),
+            [&TypePath::from("game::run"), &TypePath::from("game::stop")]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Typed values that can be passed to and returned from scripting API functions without handling
+//! raw [`Void`] pointers
+
+use std::any::TypeId;
+
+use crate::{
+    CallError, DataLayout, Dynamite, HasScriptType, HostFunctions, ReturnOwnership, SymbolId,
+    TypePath, Void,
+};
+
+/// An owned value of a type implementing [`HasScriptType`]
+///
+/// The value is stored on the heap along with its [`TypePath`] and [`DataLayout`] so that a
+/// pointer to it can be passed to a function expecting that type.
+pub struct Value {
+    /// Pointer to the boxed value
+    ptr: *mut Void,
+    /// The script path of the value's type
+    type_path: TypePath,
+    /// The memory layout of the value
+    layout: DataLayout,
+    /// The Rust type of the value, used for downcasting
+    type_id: TypeId,
+    /// Function used to drop the boxed value
+    drop_fn: unsafe fn(*mut Void),
+}
+
+impl Value {
+    /// Create a new value
+    pub fn new<T: HasScriptType + 'static>(value: T) -> Self {
+        unsafe fn drop_box<T>(ptr: *mut Void) {
+            drop(Box::from_raw(ptr as *mut T));
+        }
+
+        Self {
+            ptr: Box::into_raw(Box::new(value)) as *mut Void,
+            type_path: T::script_path(),
+            layout: DataLayout::of::<T>(),
+            type_id: TypeId::of::<T>(),
+            drop_fn: drop_box::<T>,
+        }
+    }
+
+    /// Get the [`TypePath`] of the value's type
+    pub fn type_path(&self) -> &TypePath {
+        &self.type_path
+    }
+
+    /// Get the memory layout of the value
+    pub fn layout(&self) -> DataLayout {
+        self.layout
+    }
+
+    /// Get a pointer to the value that can be passed to [`HostFunctions::call_function`]
+    ///
+    /// [`HostFunctions::call_function`]: crate::HostFunctions::call_function
+    pub fn as_ptr(&self) -> *const Void {
+        self.ptr
+    }
+
+    /// Get a reference to the value if it is of type `T`
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if self.type_id == TypeId::of::<T>() {
+            Some(unsafe { &*(self.ptr as *const T) })
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        unsafe { (self.drop_fn)(self.ptr) }
+    }
+}
+
+impl std::fmt::Debug for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Value")
+            .field("type_path", &self.type_path)
+            .field("layout", &self.layout)
+            .finish()
+    }
+}
+
+/// A list of typed arguments for a function call
+///
+/// # Example
+///
+/// ```
+/// # use dynamite::*;
+/// let args = ArgBuffer::new().with(32i32).with(44i32);
+///
+/// assert_eq!(args.len(), 2);
+/// assert_eq!(args.type_paths()[0], <i32 as HasScriptType>::script_path());
+/// ```
+#[derive(Default, Debug)]
+pub struct ArgBuffer {
+    /// The argument values
+    values: Vec<Value>,
+    /// Pointers to the argument values
+    pointers: Vec<*const Void>,
+    /// The type paths of the argument values
+    type_paths: Vec<TypePath>,
+}
+
+impl ArgBuffer {
+    /// Create an empty argument buffer
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an argument to the end of the buffer
+    pub fn push<T: HasScriptType + 'static>(&mut self, value: T) -> &mut Self {
+        let value = Value::new(value);
+
+        self.pointers.push(value.as_ptr());
+        self.type_paths.push(value.type_path().clone());
+        self.values.push(value);
+
+        self
+    }
+
+    /// Add an argument to the end of the buffer and return the buffer
+    pub fn with<T: HasScriptType + 'static>(mut self, value: T) -> Self {
+        self.push(value);
+        self
+    }
+
+    /// Get the argument values
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Get pointers to the argument values
+    pub fn pointers(&self) -> &[*const Void] {
+        &self.pointers
+    }
+
+    /// Get the type paths of the argument values
+    pub fn type_paths(&self) -> &[TypePath] {
+        &self.type_paths
+    }
+
+    /// Get the number of arguments
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether or not there are no arguments in the buffer
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// The value returned by a function called with [`Dynamite::call`]
+///
+/// If the function returns an [`OwnedByCaller`][ReturnOwnership::OwnedByCaller] value, the value is
+/// freed by the adapter that provides the function when the [`ReturnValue`] is dropped.
+///
+/// [`Dynamite::call`]: crate::Dynamite::call
+pub struct ReturnValue<'a> {
+    /// The dynamite host used to free the value
+    dynamite: &'a Dynamite,
+    /// The symbol of the function that returned the value
+    function_symbol: SymbolId,
+    /// The path to the function that returned the value
+    function_path: TypePath,
+    /// The pointer returned by the function
+    ptr: *const Void,
+    /// The declared return type of the function
+    type_path: Option<TypePath>,
+    /// The ownership of the returned pointer
+    ownership: ReturnOwnership,
+}
+
+impl<'a> ReturnValue<'a> {
+    pub(crate) fn new(
+        dynamite: &'a Dynamite,
+        function_symbol: SymbolId,
+        function_path: TypePath,
+        ptr: *const Void,
+        type_path: Option<TypePath>,
+        ownership: ReturnOwnership,
+    ) -> Self {
+        Self {
+            dynamite,
+            function_symbol,
+            function_path,
+            ptr,
+            type_path,
+            ownership,
+        }
+    }
+
+    /// Get the ownership of the returned value
+    pub fn ownership(&self) -> ReturnOwnership {
+        self.ownership
+    }
+
+    /// Get the [`TypePath`] of the returned value, or `None` if the function does not return a
+    /// value
+    pub fn type_path(&self) -> Option<&TypePath> {
+        self.type_path.as_ref()
+    }
+
+    /// Get the raw pointer returned by the function
+    pub fn as_ptr(&self) -> *const Void {
+        self.ptr
+    }
+
+    /// Whether the function returned a null pointer, which means that there is no value for
+    /// functions with an [`optional_return`][crate::FunctionDefinition::optional_return]
+    pub fn is_none(&self) -> bool {
+        self.ptr.is_null()
+    }
+
+    /// Get a copy of the returned value, or `None` if the function returned a null pointer
+    ///
+    /// Returns an error if the function does not return a value of type `T`.
+    pub fn get_optional<T: HasScriptType + Clone>(&self) -> Result<Option<T>, CallError> {
+        self.check_type::<T>()?;
+
+        if self.is_none() {
+            Ok(None)
+        } else {
+            self.get().map(Some)
+        }
+    }
+
+    /// Make sure that the function returns a value of type `T`
+    fn check_type<T: HasScriptType>(&self) -> Result<(), CallError> {
+        let type_path = self
+            .type_path
+            .as_ref()
+            .ok_or_else(|| CallError::NoReturnValue(self.function_path.clone()))?;
+
+        if type_path != &T::script_path() {
+            return Err(CallError::ReturnTypeMismatch {
+                path: self.function_path.clone(),
+                declared: type_path.clone(),
+                requested: T::script_path(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Get a copy of the returned value
+    ///
+    /// Returns an error if the function does not return a value of type `T`.
+    pub fn get<T: HasScriptType + Clone>(&self) -> Result<T, CallError> {
+        self.check_type::<T>()?;
+
+        if self.ptr.is_null() {
+            return Err(CallError::AdapterError(format!(
+                "Function `{}` returned a null pointer",
+                self.function_path
+            )));
+        }
+
+        Ok(unsafe { &*(self.ptr as *const T) }.clone())
+    }
+}
+
+impl<'a> Drop for ReturnValue<'a> {
+    fn drop(&mut self) {
+        if self.ownership == ReturnOwnership::OwnedByCaller && !self.ptr.is_null() {
+            // There is no way to report an error from `drop` and the pointer cannot be used
+            // afterwards either way, so the result is ignored
+            let _ = unsafe { self.dynamite.free_value(self.function_symbol, self.ptr) };
+        }
+    }
+}
+
+impl<'a> std::fmt::Debug for ReturnValue<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReturnValue")
+            .field("function_path", &self.function_path)
+            .field("ptr", &self.ptr)
+            .field("type_path", &self.type_path)
+            .field("ownership", &self.ownership)
+            .finish()
+    }
+}
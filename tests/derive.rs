@@ -0,0 +1,84 @@
+use dynamite::*;
+
+use std::mem::offset_of;
+
+#[derive(HasScriptType)]
+#[repr(C)]
+struct Pair(u16, u8);
+
+#[derive(HasScriptType)]
+#[repr(C)]
+struct Mixed {
+    flag: u8,
+    letter: char,
+    value: f64,
+    pair: Pair,
+}
+
+fn struct_definition<T: HasScriptType>() -> StructDefinition {
+    match T::script_type() {
+        ScriptType::Struct(definition) => definition,
+        other => panic!("Expected a struct, got {:?}", other),
+    }
+}
+
+#[test]
+fn derived_structs_match_the_rust_layout() {
+    let definition = struct_definition::<Mixed>();
+    assert_eq!(Mixed::script_path(), "derive::Mixed");
+    assert_eq!(definition.layout, DataLayout::of::<Mixed>());
+    definition.validate_layout().unwrap();
+
+    let expected = [
+        (
+            "flag",
+            u8::script_path(),
+            offset_of!(Mixed, flag),
+            DataLayout::of::<u8>(),
+        ),
+        (
+            "letter",
+            char::script_path(),
+            offset_of!(Mixed, letter),
+            DataLayout::of::<char>(),
+        ),
+        (
+            "value",
+            f64::script_path(),
+            offset_of!(Mixed, value),
+            DataLayout::of::<f64>(),
+        ),
+        (
+            "pair",
+            Pair::script_path(),
+            offset_of!(Mixed, pair),
+            DataLayout::of::<Pair>(),
+        ),
+    ];
+    for (name, type_path, offset, layout) in &expected {
+        let field = definition.field(name).unwrap();
+        assert_eq!(&field.type_path, type_path);
+        assert_eq!(field.offset, *offset);
+        assert_eq!(field.layout, *layout);
+    }
+}
+
+#[test]
+fn derived_tuple_structs_name_fields_by_index() {
+    let definition = struct_definition::<Pair>();
+    assert_eq!(definition.layout, DataLayout::of::<Pair>());
+    definition.validate_layout().unwrap();
+
+    let fields = match &definition.component_type {
+        DataType::Struct { fields } => fields,
+        other => panic!("Expected struct fields, got {:?}", other),
+    };
+    let fields = fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.offset))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [("0", offset_of!(Pair, 0)), ("1", offset_of!(Pair, 1))]
+    );
+}
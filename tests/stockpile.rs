@@ -0,0 +1,411 @@
+use dynamite::*;
+use safer_ffi::prelude::*;
+use std::sync::{Mutex, RwLock};
+
+#[stockpile_function]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[stockpile_function]
+fn positive(value: i32) -> Option<i32> {
+    Some(value).filter(|&value| value > 0)
+}
+
+#[stockpile_function]
+fn checked_div(a: i32, b: i32) -> Result<i32, String> {
+    a.checked_div(b)
+        .ok_or_else(|| format!("Cannot divide {} by {}", a, b))
+}
+
+#[stockpile_function]
+fn parse_positive(text: String) -> Result<Option<i32>, std::num::ParseIntError> {
+    Ok(positive(text.parse()?))
+}
+
+#[stockpile_function]
+fn greet(name: &str) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[stockpile_function]
+fn shout(text: String) -> String {
+    text.to_uppercase()
+}
+
+#[stockpile_function]
+fn language() -> &'static str {
+    "Rust"
+}
+
+#[stockpile_const]
+const MAX_PLAYERS: u8 = 4;
+
+#[stockpile_const]
+const TITLE: &str = "Dynamite";
+
+#[stockpile_global]
+static SPEED: RwLock<f32> = RwLock::new(1.5);
+
+#[stockpile_global]
+static SCORE: Mutex<u32> = Mutex::new(0);
+
+#[stockpile_global]
+static GRAVITY: f32 = 9.81;
+
+#[derive(HasScriptType, Clone)]
+#[repr(C)]
+struct Counter {
+    count: u32,
+}
+
+#[stockpile_impl]
+impl Counter {
+    fn new(count: u32) -> Self {
+        Self { count }
+    }
+
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    fn increment(&mut self, amount: u32) {
+        self.count += amount;
+    }
+
+    fn into_count(self) -> u32 {
+        self.count
+    }
+}
+
+fn dynamite() -> Dynamite {
+    let mut dynamite = Dynamite::new();
+    dynamite.add_stockpile().unwrap();
+    dynamite
+}
+
+#[test]
+fn proxies_check_the_argument_count() {
+    let dynamite = dynamite();
+    let a = 1i32;
+
+    let error = unsafe {
+        dynamite.call_function(&"stockpile::add".into(), &[&a as *const i32 as *const Void])
+    }
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        CallError::WrongArity { path, expected: 2, found: 1 } if path == "stockpile::add"
+    ));
+
+    let error = unsafe { dynamite.call_function(&"stockpile::Counter::increment".into(), &[]) }
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        CallError::WrongArity { path, expected: 2, found: 0 }
+            if path == "stockpile::Counter::increment"
+    ));
+}
+
+#[test]
+fn owned_return_values_are_freed() {
+    let dynamite = dynamite();
+    let value = -1i32;
+
+    // `None` is returned as a null pointer, which is freed without doing anything
+    let none = unsafe {
+        dynamite.call_function(
+            &"stockpile::positive".into(),
+            &[&value as *const i32 as *const Void],
+        )
+    }
+    .unwrap();
+    assert!(none.is_null());
+    let positive = dynamite.resolve_symbol("stockpile::positive").unwrap();
+    unsafe { dynamite.free_value(positive, none) }.unwrap();
+
+    // Functions that don't return owned values have nothing to free
+    let increment = dynamite
+        .resolve_symbol("stockpile::Counter::increment")
+        .unwrap();
+    assert!(matches!(
+        unsafe { dynamite.free_value(increment, none) },
+        Err(CallError::NotOwnedByCaller(_))
+    ));
+    assert!(matches!(
+        dynamite.resolve_symbol("stockpile::missing"),
+        Err(CallError::NotFound(_))
+    ));
+}
+
+#[test]
+fn strings_are_passed_as_safer_ffi_strings() {
+    let dynamite = dynamite();
+    let definition = dynamite
+        .get_function_definition("stockpile::greet")
+        .unwrap();
+    assert_eq!(definition.arguments[0].type_path, "std::str");
+    assert_eq!(definition.return_type.as_ref().unwrap(), "std::String");
+    assert_eq!(definition.return_ownership, ReturnOwnership::OwnedByCaller);
+
+    let args = ArgBuffer::new().with(str::Ref::from("World"));
+    let greeting = dynamite.call(&"stockpile::greet".into(), &args).unwrap();
+    let greeting = unsafe { &*(greeting.as_ptr() as *const repr_c::String) };
+    assert_eq!(&**greeting, "Hello, World!");
+
+    let args = ArgBuffer::new().with(repr_c::String::from("text".to_string()));
+    let shouted = dynamite.call(&"stockpile::shout".into(), &args).unwrap();
+    let shouted = unsafe { &*(shouted.as_ptr() as *const repr_c::String) };
+    assert_eq!(&**shouted, "TEXT");
+
+    // Returned `&str`s are copied into owned strings
+    let args = ArgBuffer::new();
+    let language = dynamite.call(&"stockpile::language".into(), &args).unwrap();
+    assert_eq!(language.ownership(), ReturnOwnership::OwnedByCaller);
+    let language = unsafe { &*(language.as_ptr() as *const repr_c::String) };
+    assert_eq!(&**language, "Rust");
+}
+
+#[test]
+fn methods_are_added_to_the_type_definition() {
+    let dynamite = dynamite();
+    let symbol = dynamite.resolve_symbol("stockpile::Counter").unwrap();
+    let definition = match dynamite.get_script_type(symbol) {
+        Some(ScriptType::Struct(definition)) => definition,
+        other => panic!("Expected a struct, got {:?}", other),
+    };
+
+    // The order of the methods is up to the stockpile
+    let mut receivers = definition
+        .method_definitions
+        .iter()
+        .map(|method| (method.name.as_str(), method.receiver))
+        .collect::<Vec<_>>();
+    receivers.sort_by_key(|(name, _)| *name);
+    assert_eq!(
+        receivers,
+        [
+            ("count", Receiver::Shared),
+            ("increment", Receiver::Mutable),
+            ("into_count", Receiver::Value),
+            ("new", Receiver::Static),
+        ]
+    );
+
+    // The `self` argument is the first argument of the method's function
+    let increment = dynamite
+        .get_function_definition("stockpile::Counter::increment")
+        .unwrap();
+    assert_eq!(increment.arguments[0].type_path, "stockpile::Counter");
+    assert_eq!(increment.arguments[1].type_path, "std::u32");
+}
+
+#[test]
+fn methods_are_called_with_self_as_the_first_argument() {
+    let dynamite = dynamite();
+
+    let args = ArgBuffer::new().with(2u32);
+    let counter: Counter = dynamite
+        .call(&"stockpile::Counter::new".into(), &args)
+        .unwrap()
+        .get()
+        .unwrap();
+    assert_eq!(counter.count, 2);
+
+    // Mutable methods modify the instance that is passed to them
+    let mut counter = counter;
+    let amount = 3u32;
+    unsafe {
+        dynamite.call_function(
+            &"stockpile::Counter::increment".into(),
+            &[
+                &mut counter as *mut Counter as *const Void,
+                &amount as *const u32 as *const Void,
+            ],
+        )
+    }
+    .unwrap();
+    assert_eq!(counter.count, 5);
+
+    let args = ArgBuffer::new().with(counter.clone());
+    let count: u32 = dynamite
+        .call(&"stockpile::Counter::count".into(), &args)
+        .unwrap()
+        .get()
+        .unwrap();
+    assert_eq!(count, 5);
+
+    // Methods taking `self` by value get a copy of the instance
+    let count: u32 = dynamite
+        .call(&"stockpile::Counter::into_count".into(), &args)
+        .unwrap()
+        .get()
+        .unwrap();
+    assert_eq!(count, 5);
+}
+
+#[test]
+fn errors_are_returned_as_script_errors() {
+    let dynamite = dynamite();
+    let definition = dynamite
+        .get_function_definition("stockpile::checked_div")
+        .unwrap();
+    assert!(definition.fallible);
+    assert!(!definition.optional_return);
+
+    let args = ArgBuffer::new().with(6i32).with(3i32);
+    let quotient: i32 = dynamite
+        .call(&"stockpile::checked_div".into(), &args)
+        .unwrap()
+        .get()
+        .unwrap();
+    assert_eq!(quotient, 2);
+
+    let args = ArgBuffer::new().with(1i32).with(0i32);
+    match dynamite.call(&"stockpile::checked_div".into(), &args).err() {
+        Some(CallError::Script(error)) => assert_eq!(error.message, "Cannot divide 1 by 0"),
+        other => panic!("Expected a script error, got {:?}", other),
+    }
+}
+
+#[test]
+fn none_is_returned_as_null() {
+    let dynamite = dynamite();
+    let definition = dynamite
+        .get_function_definition("stockpile::positive")
+        .unwrap();
+    assert!(definition.optional_return);
+    assert!(!definition.fallible);
+
+    let args = ArgBuffer::new().with(4i32);
+    let value = dynamite.call(&"stockpile::positive".into(), &args).unwrap();
+    assert_eq!(value.get_optional::<i32>().unwrap(), Some(4));
+
+    let args = ArgBuffer::new().with(-4i32);
+    let value = dynamite.call(&"stockpile::positive".into(), &args).unwrap();
+    assert!(value.is_none());
+    assert_eq!(value.get_optional::<i32>().unwrap(), None);
+}
+
+#[test]
+fn optional_results_are_returned_as_null_or_script_errors() {
+    let dynamite = dynamite();
+    let path = "stockpile::parse_positive".into();
+    let definition = dynamite
+        .get_function_definition("stockpile::parse_positive")
+        .unwrap();
+    assert!(definition.fallible);
+    assert!(definition.optional_return);
+    assert_eq!(definition.return_type.as_ref().unwrap(), "std::i32");
+
+    let parse = |text: &str| {
+        let args = ArgBuffer::new().with(repr_c::String::from(text.to_string()));
+        dynamite
+            .call(&path, &args)
+            .and_then(|value| value.get_optional::<i32>())
+    };
+    assert_eq!(parse("7").unwrap(), Some(7));
+    assert_eq!(parse("-7").unwrap(), None);
+    assert!(matches!(parse("seven"), Err(CallError::Script(_))));
+}
+
+#[test]
+fn constants_are_added_to_the_api() {
+    let dynamite = dynamite();
+
+    assert_eq!(
+        dynamite.get_constant("stockpile::MAX_PLAYERS"),
+        Some(&LiteralValue::UInt(4))
+    );
+    assert_eq!(
+        dynamite.get_constant("stockpile::TITLE"),
+        Some(&LiteralValue::String("Dynamite".into()))
+    );
+    let symbol = dynamite.resolve_symbol("stockpile::TITLE").unwrap();
+    assert!(matches!(
+        dynamite.get_script_type(symbol),
+        Some(ScriptType::Constant { type_path, .. }) if type_path == "std::str"
+    ));
+
+    // Other kinds of types aren't constants
+    assert_eq!(dynamite.get_constant("stockpile::GRAVITY"), None);
+    assert_eq!(dynamite.get_constant("stockpile::missing"), None);
+}
+
+#[test]
+fn locked_globals_can_be_read_and_written() {
+    let dynamite = dynamite();
+    let speed = "stockpile::SPEED".into();
+    let score = "stockpile::SCORE".into();
+
+    assert_eq!(dynamite.read_global::<f32>(&speed).unwrap(), 1.5);
+    dynamite.write_global(&speed, 3.0f32).unwrap();
+    assert_eq!(dynamite.read_global::<f32>(&speed).unwrap(), 3.0);
+    assert_eq!(*SPEED.read().unwrap(), 3.0);
+
+    dynamite.write_global(&score, 10u32).unwrap();
+    assert_eq!(*SCORE.lock().unwrap(), 10);
+    *SCORE.lock().unwrap() += 1;
+    assert_eq!(dynamite.read_global::<u32>(&score).unwrap(), 11);
+
+    // Globals can also be accessed by symbol through pointers to their values
+    let symbol = dynamite.resolve_symbol("stockpile::SCORE").unwrap();
+    let mut value = 0u32;
+    unsafe {
+        dynamite
+            .set_global(symbol, &12u32 as *const u32 as *const Void)
+            .unwrap();
+        dynamite
+            .get_global(symbol, &mut value as *mut u32 as *mut Void)
+            .unwrap();
+    }
+    assert_eq!(value, 12);
+}
+
+#[test]
+fn plain_globals_are_read_only() {
+    let dynamite = dynamite();
+    let gravity = "stockpile::GRAVITY".into();
+
+    assert_eq!(dynamite.read_global::<f32>(&gravity).unwrap(), 9.81);
+    assert!(matches!(
+        dynamite.write_global(&gravity, 1.0f32),
+        Err(CallError::GlobalNotSettable(path)) if path == "stockpile::GRAVITY"
+    ));
+    assert!(matches!(
+        unsafe {
+            dynamite.set_global(
+                dynamite.resolve_symbol("stockpile::GRAVITY").unwrap(),
+                &1.0f32 as *const f32 as *const Void,
+            )
+        },
+        Err(CallError::GlobalNotSettable(_))
+    ));
+    assert_eq!(dynamite.read_global::<f32>(&gravity).unwrap(), 9.81);
+}
+
+#[test]
+fn globals_are_accessed_with_their_declared_type() {
+    let dynamite = dynamite();
+    let speed = "stockpile::SPEED".into();
+
+    match dynamite.read_global::<u32>(&speed) {
+        Err(CallError::GlobalTypeMismatch {
+            declared,
+            requested,
+            ..
+        }) => {
+            assert_eq!(declared, "std::f32");
+            assert_eq!(requested, "std::u32");
+        }
+        other => panic!("Expected a type mismatch, got {:?}", other),
+    }
+    assert!(matches!(
+        dynamite.write_global(&speed, 1.0f64),
+        Err(CallError::GlobalTypeMismatch { .. })
+    ));
+    assert!(matches!(
+        dynamite.read_global::<f32>(&"stockpile::TITLE".into()),
+        Err(CallError::NotAGlobal(_))
+    ));
+}